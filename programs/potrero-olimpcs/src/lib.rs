@@ -1,44 +1,331 @@
 use anchor_lang::error_code;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
 use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 use std::cmp::Ordering;
-use std::str::FromStr;
 
 declare_id!("7JawXA6bWsbYvdp98qMhp1Noo5TxUUCmmHjMWfccfRy4");
-const PROJECT_TREASURY: &str = "GtrjYbtvJ9T5oP1P64gY2yBLXcDtKERgNp5o1k6ty7Mj";
+const MAX_RESOLVERS: usize = 7;
+const MAX_AMENDMENTS: usize = 4;
+const MAX_TIED: usize = 4;
+const MAX_POSITIONS: u8 = 8;
+const MAX_PAID_PLACES: usize = 10;
+const MAX_LEADERBOARD: usize = 10;
+const MAX_SEASON_NAME: usize = 32;
+const DEFAULT_PAYOUT: [u16; MAX_PAID_PLACES] =
+    [3500, 2500, 1500, 800, 500, 400, 300, 200, 200, 100];
+const MAX_TOKEN_POOLS: usize = 4;
+const BATCH_COMPUTE_RESERVE: u64 = 25_000;
+const RANKINGS_PAGE: u32 = 200;
+const CLEANUP_RETENTION: i64 = 30 * 24 * 60 * 60;
+const CLEANUP_REWARD_BASIS_POINTS: u16 = 1_000;
+const LEGACY_EVENT_SPACE: usize = 1_000;
+const MIN_DISPUTE_BOND: u64 = 10_000_000;
+const SUPPORTED_MINT_EXTENSIONS: [ExtensionType; 7] = [
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
 
 #[program]
 pub mod potrero_olimpcs {
 
     use super::*;
 
+    pub fn init_config(ctx: Context<InitConfig>, treasury: Pubkey, fees: FeeRates) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts
+                .program
+                .programdata_address()?
+                .unwrap_or_default(),
+            ctx.accounts.program_data.key()
+        );
+        require!(
+            ctx.accounts.program_data.upgrade_authority_address
+                == Some(ctx.accounts.authority.key()),
+            PotreroError::Unauthorized
+        );
+        fees.validate()?;
+        ctx.accounts.config.set_inner(Config {
+            authority: ctx.accounts.authority.key(),
+            pending_authority: Pubkey::default(),
+            treasury,
+            fees,
+            pause: PauseFlags::default(),
+            bump: ctx.bumps.config,
+        });
+        Ok(())
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        treasury: Pubkey,
+        fees: FeeRates,
+    ) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        fees.validate()?;
+        let config = &mut ctx.accounts.config;
+        config.treasury = treasury;
+        config.fees = fees;
+        Ok(())
+    }
+
+    pub fn set_pause(ctx: Context<UpdateConfig>, pause: PauseFlags) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        ctx.accounts.config.pause = pause;
+        Ok(())
+    }
+
+    /// First half of an authority handover: the new authority only takes
+    /// over once it signs `accept_authority`.
+    pub fn transfer_authority(ctx: Context<UpdateConfig>, new_authority: Pubkey) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        ctx.accounts.config.pending_authority = new_authority;
+        Ok(())
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.pending_authority.key(),
+            ctx.accounts.config.pending_authority
+        );
+        let config = &mut ctx.accounts.config;
+        config.authority = config.pending_authority;
+        config.pending_authority = Pubkey::default();
+        Ok(())
+    }
+
+    pub fn open_season(ctx: Context<OpenSeason>, name: String) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        require!(
+            !name.is_empty() && name.len() <= MAX_SEASON_NAME,
+            PotreroError::InvalidSeasonName
+        );
+        ctx.accounts.global.set_inner(Leaderboard {
+            leaderboard: vec![],
+            settled: 0,
+            events: 0,
+            players: 0,
+            pool: 0,
+            payed: false,
+            payed_at: 0,
+            payout: DEFAULT_PAYOUT.to_vec(),
+            prizes: vec![],
+            token_pools: vec![],
+            open: true,
+            opened_by: ctx.accounts.authority.key(),
+            bump: ctx.bumps.global,
+            name,
+        });
+        Ok(())
+    }
+
+    /// Stops new events from joining the season. Its running events still
+    /// settle into it, and `pay_global` only pays closed seasons.
+    pub fn close_season(ctx: Context<CloseSeason>) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        ctx.accounts.global.open = false;
+        Ok(())
+    }
+
+    /// Creates the season's rankings with room for one page of players.
+    pub fn open_rankings(ctx: Context<OpenRankings>) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        let mut rankings = ctx.accounts.rankings.load_init()?;
+        rankings.season = ctx.accounts.global.key();
+        rankings.capacity = RANKINGS_PAGE;
+        rankings.bump = ctx.bumps.rankings;
+        Ok(())
+    }
+
+    /// Reallocates the rankings one page larger. A transaction can only
+    /// grow an account by 10 KiB, so big boards are built a page at a time.
+    pub fn grow_rankings(ctx: Context<GrowRankings>) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        ctx.accounts.rankings.load_mut()?.capacity += RANKINGS_PAGE;
+        Ok(())
+    }
+
+    /// Moves a player to their current season score on the rankings.
+    /// Anyone can crank it once the player's results are processed.
+    pub fn rank_player(ctx: Context<RankPlayer>) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.processing,
+            PotreroError::ProcessingPaused
+        );
+        let player_points = &mut ctx.accounts.player_points;
+        let entry = RankEntry {
+            pubkey: player_points.pubkey,
+            score: u64::try_from(player_points.score).map_err(|_| PotreroError::MathOverflow)?,
+            timestamp: player_points.timestamp,
+        };
+        let rankings = ctx.accounts.rankings.to_account_info();
+        let mut data = rankings.try_borrow_mut_data()?;
+        let (header, slots) = Rankings::split_mut(&mut data)?;
+        let previous = player_points.ranked_entry();
+        let (len, ranked) = rank_in(slots, header.len as usize, previous.as_ref(), entry);
+        header.len = len as u32;
+        player_points.ranked = ranked.is_some();
+        if let Some(ranked) = ranked {
+            player_points.ranked_score = ranked.score;
+            player_points.ranked_at = ranked.timestamp;
+        }
+        Ok(())
+    }
+
+    /// Returns the player's 1-based place on the season's rankings, or 0
+    /// if they aren't on them.
+    pub fn player_rank(ctx: Context<PlayerRank>) -> Result<u32> {
+        let Some(entry) = ctx.accounts.player_points.ranked_entry() else {
+            return Ok(0);
+        };
+        let rankings = ctx.accounts.rankings.to_account_info();
+        let data = rankings.try_borrow_data()?;
+        let (header, slots) = Rankings::split(&data)?;
+        Ok(slots[..header.len as usize]
+            .binary_search_by(|slot| ranking_order(slot, &entry))
+            .map_or(0, |index| index as u32 + 1))
+    }
+
+    pub fn approve_creator(ctx: Context<ApproveCreator>, creator: Pubkey) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        ctx.accounts.approval.set_inner(CreatorApproval {
+            creator,
+            approved_at: Clock::get()?.unix_timestamp,
+            bump: ctx.bumps.approval,
+        });
+        Ok(())
+    }
+
+    pub fn revoke_creator(ctx: Context<RevokeCreator>, _creator: Pubkey) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         ctx: Context<Initialize>,
         name: String,
+        positions: u8,
         resolultion_time: i64,
         open_until_time: i64,
         resolver: Pubkey,
         fee: u64,
         fee_receiver: Pubkey,
-        creator_basis_points: u16,
+        fees: Option<FeeRates>,
+        grace_period: i64,
+        backup_resolver: Pubkey,
+        arbitrator: Pubkey,
+        dispute_period: i64,
+        dispute_bond: u64,
+        scoring: ScoringRules,
+        payout: Vec<u16>,
+        withdraw_penalty: u16,
+        reveal_until: i64,
     ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.creation,
+            PotreroError::CreationPaused
+        );
+        let fees = fees.unwrap_or_else(|| ctx.accounts.config.fees.clone());
+        fees.validate()?;
+        validate_payout(&payout)?;
+        require!(ctx.accounts.global.open, PotreroError::SeasonClosed);
+        require!(withdraw_penalty <= 10_000, PotreroError::InvalidFees);
+        require!(
+            reveal_until == 0
+                || (reveal_until > open_until_time && reveal_until <= resolultion_time),
+            PotreroError::InvalidRevealWindow
+        );
+        require!(
+            positions > 0 && positions <= MAX_POSITIONS,
+            PotreroError::InvalidPositions
+        );
+        require!(
+            scoring.exact.len() == positions as usize,
+            PotreroError::InvalidScoring
+        );
+        require!(grace_period >= 0, PotreroError::InvalidGracePeriod);
+        // An open dispute holds up `process_results` until the arbitrator
+        // settles it, so opening one has to cost something.
+        require!(
+            dispute_period == 0
+                || (dispute_period > 0
+                    && arbitrator != Pubkey::default()
+                    && dispute_bond >= MIN_DISPUTE_BOND),
+            PotreroError::InvalidDisputeConfig
+        );
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .map(|mint| mint.key())
+            .unwrap_or_default();
+        require!(
+            mint == Pubkey::default() || ctx.accounts.vault.is_some(),
+            PotreroError::MissingTokenAccount
+        );
+        if let Some(mint) = &ctx.accounts.mint {
+            ensure_supported_mint(&mint.to_account_info())?;
+            // Reserves the season's pool for this mint now, so `pay` can't
+            // run into the pool limit and strand the event's funds.
+            ctx.accounts.global.add_to_token_pool(mint.key(), 0)?;
+        }
         ctx.accounts.oracle_event.set_inner(OracleEvent {
             resolultion_time,
             open_until_time,
+            reveal_until,
             resolver,
+            backup_resolver,
+            grace_period,
+            arbitrator,
+            dispute_period,
+            dispute_bond,
+            open_disputes: 0,
             solved_at: 0,
+            payed_at: 0,
+            voided_at: 0,
             bump: ctx.bumps.oracle_event,
             leaderboard: vec![],
             name,
             fee,
-            creator_basis_points,
+            withdraw_penalty,
+            mint,
+            fees,
             fee_receiver,
+            maker: ctx.accounts.maker.key(),
+            season: ctx.accounts.global.key(),
             payed: false,
+            cancelled: false,
+            abandoned: false,
             plays: 0,
             settled: 0,
-            gold: 0,
-            silver: 0,
-            bronze: 0,
+            closed: 0,
+            pool: 0,
+            payout,
+            prizes: vec![],
+            positions,
+            podium: Podium::default(),
+            scoring,
+        });
+        ctx.accounts.results.set_inner(EventResults {
+            resolvers: vec![resolver],
+            threshold: 1,
+            votes: vec![],
+            amendments: vec![],
+            bump: ctx.bumps.results,
         });
         ctx.accounts.global.events += 1;
 
@@ -48,540 +335,1178 @@ pub mod potrero_olimpcs {
     pub fn make_prediction(
         ctx: Context<MakePrediction>,
         _name: String,
-        gold: u8,
-        silver: u8,
-        bronze: u8,
+        picks: Vec<u8>,
     ) -> Result<()> {
-        require_keys_eq!(
-            ctx.accounts.fee_receiver.key(),
-            ctx.accounts.oracle_event.fee_receiver
+        require!(
+            !ctx.accounts.oracle_event.is_commit_reveal(),
+            PotreroError::WrongPredictionMode
+        );
+        validate_picks(&picks, ctx.accounts.oracle_event.positions)?;
+        enter_prediction(ctx, picks, [0; 32])
+    }
+
+    /// Enters a commit-reveal event with `sha256(picks || salt || player)`.
+    /// The picks stay hidden until `reveal_prediction`.
+    pub fn commit_prediction(
+        ctx: Context<MakePrediction>,
+        _name: String,
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.oracle_event.is_commit_reveal(),
+            PotreroError::WrongPredictionMode
+        );
+        enter_prediction(ctx, vec![], commitment)
+    }
+
+    pub fn reveal_prediction(
+        ctx: Context<RevealPrediction>,
+        _name: String,
+        picks: Vec<u8>,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.predictions,
+            PotreroError::PredictionsPaused
+        );
+        ctx.accounts.oracle_event.ensure_active()?;
+        let clock = Clock::get()?;
+        if clock.unix_timestamp <= ctx.accounts.oracle_event.open_until_time
+            || clock.unix_timestamp > ctx.accounts.oracle_event.reveal_until
+        {
+            return Err(PotreroError::RevealWindowClosed.into());
+        }
+        validate_picks(&picks, ctx.accounts.oracle_event.positions)?;
+        require!(
+            commitment(&picks, &salt, &ctx.accounts.player.key())
+                == ctx.accounts.prediction.commitment,
+            PotreroError::InvalidReveal
+        );
+        ctx.accounts.prediction.picks = picks;
+        Ok(())
+    }
+
+    /// Records the official podium, one list of athletes per position.
+    /// This replaced the first deployment's `resolve(gold, silver, bronze)`
+    /// and breaks its clients: a single-winner result is now sent as
+    /// `[[gold], [silver], [bronze]]`. Events stored in the old layout are
+    /// converted with `migrate_event`.
+    pub fn resolve(ctx: Context<OracleResolve>, places: Vec<Vec<u8>>) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.resolution,
+            PotreroError::ResolutionPaused
+        );
+        ctx.accounts.oracle_event.ensure_active()?;
+        let podium = Podium::new(places, ctx.accounts.oracle_event.positions)?;
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        if now < ctx.accounts.oracle_event.resolultion_time {
+            return Err(PotreroError::EventOpen.into());
+        }
+        if ctx.accounts.oracle_event.solved_at != 0 {
+            return Err(PotreroError::EventClose.into());
+        }
+        let resolver = ctx.accounts.resolver.key();
+        if ctx.accounts.results.resolvers.contains(&resolver) {
+            let vote = ResolverVote {
+                resolver,
+                podium: podium.clone(),
+            };
+            if !ctx.accounts.results.record_vote(vote)? {
+                return Ok(());
+            }
+        } else {
+            require_keys_eq!(resolver, ctx.accounts.oracle_event.backup_resolver);
+            if now <= ctx.accounts.oracle_event.resolution_deadline() {
+                return Err(PotreroError::ResolverGracePeriod.into());
+            }
+        }
+        ctx.accounts.oracle_event.podium = podium;
+        ctx.accounts.oracle_event.solved_at = now;
+        Ok(())
+    }
+
+    pub fn update_prediction(
+        ctx: Context<UpdatePrediction>,
+        _name: String,
+        picks: Vec<u8>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.predictions,
+            PotreroError::PredictionsPaused
+        );
+        ctx.accounts.oracle_event.ensure_active()?;
+        let clock = Clock::get()?;
+        if clock.unix_timestamp > ctx.accounts.oracle_event.open_until_time
+            || ctx.accounts.oracle_event.solved_at != 0
+        {
+            return Err(PotreroError::EventClose.into());
+        }
+        require!(
+            !ctx.accounts.oracle_event.is_commit_reveal(),
+            PotreroError::WrongPredictionMode
+        );
+        validate_picks(&picks, ctx.accounts.oracle_event.positions)?;
+        ctx.accounts.prediction.picks = picks;
+        ctx.accounts.prediction.timestamp = clock.unix_timestamp;
+        Ok(())
+    }
+
+    /// Takes a prediction back while the event is open. The event's
+    /// `withdraw_penalty` stays in the pool for the remaining players.
+    pub fn withdraw_prediction(ctx: Context<WithdrawPrediction>, _name: String) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.predictions,
+            PotreroError::PredictionsPaused
         );
+        ctx.accounts.oracle_event.ensure_active()?;
         let clock = Clock::get()?;
         if clock.unix_timestamp > ctx.accounts.oracle_event.open_until_time
             || ctx.accounts.oracle_event.solved_at != 0
         {
             return Err(PotreroError::EventClose.into());
         }
+        ctx.accounts.player_points.events -= 1;
+
+        let event = &mut ctx.accounts.oracle_event;
+        let refund = event.fee - basis_points_of(event.fee, event.withdraw_penalty);
+        event.plays -= 1;
+        event.pool -= refund;
+        if event.is_token() {
+            let bump = [event.bump];
+            let seeds = [b"OracleEvent".as_ref(), event.name.as_bytes(), &bump];
+            return transfer_tokens(
+                &ctx.accounts.token_program,
+                &ctx.accounts.mint,
+                &ctx.accounts.vault,
+                &ctx.accounts.player_token,
+                event.to_account_info(),
+                &[&seeds],
+                refund,
+            );
+        }
+        event.sub_lamports(refund)?;
+        ctx.accounts.player.add_lamports(refund)?;
+        Ok(())
+    }
+
+    /// Rewrites an event created by the first deployment, which can't be
+    /// read with the current layout, into the given season. It keeps its
+    /// resolver, fee and any podium already resolved; the caller becomes its
+    /// maker and pays for the results account and any extra rent. Its
+    /// predictions then move over with `migrate_prediction`. Events the first
+    /// deployment already processed or paid are refused, as their pool and
+    /// scores no longer match their predictions.
+    pub fn migrate_event(ctx: Context<MigrateEvent>, _name: String) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        require!(ctx.accounts.global.open, PotreroError::SeasonClosed);
+        let event_info = ctx.accounts.oracle_event.to_account_info();
+        let legacy = LegacyOracleEvent::load(&event_info)?;
+        require!(
+            !legacy.payed && legacy.settled == 0,
+            PotreroError::LegacyEventProcessed
+        );
+        let positions = 3;
+        let podium = if legacy.solved_at == 0 {
+            Podium::default()
+        } else {
+            Podium::new(
+                vec![vec![legacy.gold], vec![legacy.silver], vec![legacy.bronze]],
+                positions,
+            )?
+        };
+        let fees = FeeRates {
+            creator_basis_points: legacy.creator_basis_points,
+            ..ctx.accounts.config.fees.clone()
+        };
+        fees.validate()?;
+        // Every legacy prediction paid its fee into the event.
+        let pool = u64::try_from(legacy.plays)
+            .ok()
+            .and_then(|plays| plays.checked_mul(legacy.fee))
+            .ok_or(PotreroError::MathOverflow)?;
+        let event = OracleEvent {
+            resolultion_time: legacy.resolultion_time,
+            open_until_time: legacy.open_until_time,
+            reveal_until: 0,
+            resolver: legacy.resolver,
+            backup_resolver: Pubkey::default(),
+            grace_period: 0,
+            arbitrator: Pubkey::default(),
+            dispute_period: 0,
+            dispute_bond: 0,
+            open_disputes: 0,
+            solved_at: legacy.solved_at,
+            payed_at: 0,
+            voided_at: 0,
+            bump: ctx.bumps.oracle_event,
+            leaderboard: vec![],
+            name: legacy.name,
+            fee: legacy.fee,
+            withdraw_penalty: 0,
+            mint: Pubkey::default(),
+            fees,
+            fee_receiver: legacy.fee_receiver,
+            maker: ctx.accounts.authority.key(),
+            season: ctx.accounts.global.key(),
+            payed: false,
+            cancelled: false,
+            abandoned: false,
+            plays: legacy.plays,
+            settled: 0,
+            closed: 0,
+            pool,
+            payout: vec![10_000],
+            prizes: vec![],
+            positions,
+            podium,
+            // The first deployment never scored; legacy events use the
+            // plain 3-2-1 schedule.
+            scoring: ScoringRules {
+                exact: vec![3, 2, 1],
+                on_podium: 0,
+                perfect_bonus: 0,
+            },
+        };
+
+        let space = OracleEvent::space(positions);
+        let needed = Rent::get()?.minimum_balance(space) + pool;
+        if needed > event_info.lamports() {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: event_info.clone(),
+                },
+            );
+            system_program::transfer(cpi_context, needed - event_info.lamports())?;
+        }
+        event_info.realloc(space, false)?;
+        event.try_serialize(&mut &mut event_info.try_borrow_mut_data()?[..])?;
+        ctx.accounts.results.set_inner(EventResults {
+            resolvers: vec![legacy.resolver],
+            threshold: 1,
+            votes: vec![],
+            amendments: vec![],
+            bump: ctx.bumps.results,
+        });
+        ctx.accounts.global.events += 1;
+        Ok(())
+    }
+
+    /// Moves a prediction made under the old `plays`-indexed address to its
+    /// event-and-player address, once its event went through
+    /// `migrate_event`. `index` is the `plays` count it was made at. The
+    /// player's `lock` proves the prediction hasn't been processed yet and
+    /// is closed together with the old account. Anyone can crank it: the
+    /// cranker pays for the new accounts and the old ones' rent goes to the
+    /// player.
+    pub fn migrate_prediction(
+        ctx: Context<MigratePrediction>,
+        name: String,
+        index: u128,
+    ) -> Result<()> {
+        let legacy_info = ctx.accounts.legacy_prediction.to_account_info();
+        require_keys_eq!(*legacy_info.owner, crate::ID);
+        let legacy = {
+            let data = legacy_info.try_borrow_data()?;
+            require!(
+                data.starts_with(&PodiumPrediction::DISCRIMINATOR),
+                anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+            );
+            LegacyPrediction::deserialize(&mut &data[8..])?
+        };
+        let legacy_address = Pubkey::create_program_address(
+            &[
+                b"PodiumPrediction",
+                name.as_bytes(),
+                &index.to_le_bytes(),
+                &[legacy.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| anchor_lang::error::ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(
+            legacy_info.key(),
+            legacy_address,
+            anchor_lang::error::ErrorCode::ConstraintSeeds
+        );
+        require!(
+            index < ctx.accounts.oracle_event.plays,
+            anchor_lang::error::ErrorCode::ConstraintSeeds
+        );
+        require_keys_eq!(legacy.owner, ctx.accounts.player.key());
+        require_keys_eq!(legacy.event, ctx.accounts.oracle_event.key());
         ctx.accounts.prediction.set_inner(PodiumPrediction {
-            gold,
-            silver,
-            bronze,
-            event: *ctx.accounts.oracle_event.to_account_info().key,
-            timestamp: clock.unix_timestamp,
-            owner: ctx.accounts.player.key(),
+            picks: vec![legacy.gold, legacy.silver, legacy.bronze],
+            event: legacy.event,
+            commitment: [0; 32],
+            timestamp: legacy.timestamp,
+            owner: legacy.owner,
+            settled_at: 0,
+            points: 0,
             bump: ctx.bumps.prediction,
         });
-        ctx.accounts.oracle_event.plays += 1;
-        ctx.accounts.lock.set_inner(Lock {
-            bump: ctx.bumps.lock,
-        });
-        ctx.accounts.player_points.set_inner(PlayerPoints {
-            pubkey: ctx.accounts.player.key(),
-            score: ctx.accounts.player_points.score,
-            timestamp: clock.unix_timestamp,
-            events: ctx.accounts.player_points.events + 1,
-            settled: ctx.accounts.player_points.settled,
-            bump: ctx.bumps.player_points,
+        let points = &mut ctx.accounts.player_points;
+        if points.pubkey == Pubkey::default() {
+            ctx.accounts.global.players += 1;
+        }
+        points.pubkey = legacy.owner;
+        points.timestamp = points.timestamp.max(legacy.timestamp);
+        points.events += 1;
+        points.bump = ctx.bumps.player_points;
+
+        close_program_account(&legacy_info, &ctx.accounts.player)
+    }
+
+    pub fn open_dispute(ctx: Context<OpenDispute>, _name: String) -> Result<()> {
+        ctx.accounts.oracle_event.ensure_active()?;
+        if ctx.accounts.oracle_event.solved_at == 0 {
+            return Err(PotreroError::EventNotResolved.into());
+        }
+        let clock = Clock::get()?;
+        if clock.unix_timestamp >= ctx.accounts.oracle_event.dispute_deadline() {
+            return Err(PotreroError::DisputeWindowClosed.into());
+        }
+        ctx.accounts.dispute.set_inner(Dispute {
+            player: ctx.accounts.player.key(),
+            event: ctx.accounts.oracle_event.key(),
+            bond: ctx.accounts.oracle_event.dispute_bond,
+            opened_at: clock.unix_timestamp,
+            bump: ctx.bumps.dispute,
         });
+        ctx.accounts.oracle_event.open_disputes += 1;
         let cpi_context = CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
                 from: ctx.accounts.player.to_account_info().clone(),
-                to: ctx.accounts.oracle_event.to_account_info().clone(),
+                to: ctx.accounts.dispute.to_account_info().clone(),
             },
         );
-        system_program::transfer(cpi_context, ctx.accounts.oracle_event.fee)?;
+        system_program::transfer(cpi_context, ctx.accounts.oracle_event.dispute_bond)?;
         Ok(())
     }
 
-    pub fn resolve(ctx: Context<OracleResolve>, gold: u8, silver: u8, bronze: u8) -> Result<()> {
+    /// Takes the podium in the same shape as `resolve`.
+    pub fn amend_results(
+        ctx: Context<AmendResults>,
+        _name: String,
+        places: Vec<Vec<u8>>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.resolution,
+            PotreroError::ResolutionPaused
+        );
         require_keys_eq!(
-            ctx.accounts.resolver.key(),
-            ctx.accounts.oracle_event.resolver
+            ctx.accounts.arbitrator.key(),
+            ctx.accounts.oracle_event.arbitrator
         );
+        ctx.accounts.oracle_event.ensure_active()?;
+        if ctx.accounts.oracle_event.solved_at == 0 {
+            return Err(PotreroError::EventNotResolved.into());
+        }
         let clock = Clock::get()?;
-        let now = clock.unix_timestamp;
-        if now < ctx.accounts.oracle_event.resolultion_time {
-            return Err(PotreroError::EventOpen.into());
+        if clock.unix_timestamp >= ctx.accounts.oracle_event.dispute_deadline()
+            && ctx.accounts.oracle_event.open_disputes == 0
+        {
+            return Err(PotreroError::DisputeWindowClosed.into());
         }
-        ctx.accounts.oracle_event.gold = gold;
-        ctx.accounts.oracle_event.silver = silver;
-        ctx.accounts.oracle_event.bronze = bronze;
-        ctx.accounts.oracle_event.solved_at = now;
+        require!(
+            ctx.accounts.results.amendments.len() < MAX_AMENDMENTS,
+            PotreroError::TooManyAmendments
+        );
+        let after = Podium::new(places, ctx.accounts.oracle_event.positions)?;
+        ctx.accounts.results.amendments.push(Amendment {
+            before: ctx.accounts.oracle_event.podium.clone(),
+            after: after.clone(),
+            amended_at: clock.unix_timestamp,
+        });
+        ctx.accounts.oracle_event.podium = after;
         Ok(())
     }
 
-    pub fn process_results(ctx: Context<ProcessResults>) -> Result<()> {
+    pub fn settle_dispute(ctx: Context<SettleDispute>, _name: String, upheld: bool) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.resolution,
+            PotreroError::ResolutionPaused
+        );
         require_keys_eq!(
-            ctx.accounts.project_treasury.key(),
-            Pubkey::from_str(PROJECT_TREASURY).unwrap()
+            ctx.accounts.arbitrator.key(),
+            ctx.accounts.oracle_event.arbitrator
         );
         require_keys_eq!(
-            ctx.accounts.oracle_event.key(),
-            ctx.accounts.prediction.event
+            ctx.accounts.project_treasury.key(),
+            ctx.accounts.config.treasury
         );
-        if ctx.accounts.oracle_event.solved_at == 0 {
-            return Err(PotreroError::EventNotResolved.into());
-        }
-        let mut points: u128 = 0;
-        if ctx.accounts.oracle_event.gold == ctx.accounts.prediction.gold {
-            points += 3;
+        if !upheld {
+            let bond = ctx.accounts.dispute.bond;
+            ctx.accounts.dispute.sub_lamports(bond)?;
+            ctx.accounts.project_treasury.add_lamports(bond)?;
         }
-        if ctx.accounts.oracle_event.silver == ctx.accounts.prediction.silver {
-            points += 2;
+        ctx.accounts.oracle_event.open_disputes -= 1;
+        Ok(())
+    }
+
+    pub fn set_resolvers(
+        ctx: Context<SetResolvers>,
+        _name: String,
+        resolvers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require_keys_eq!(
+            ctx.accounts.resolver.key(),
+            ctx.accounts.oracle_event.resolver
+        );
+        ctx.accounts.oracle_event.ensure_active()?;
+        if ctx.accounts.oracle_event.solved_at != 0 || !ctx.accounts.results.votes.is_empty() {
+            return Err(PotreroError::EventClose.into());
         }
-        if ctx.accounts.oracle_event.bronze == ctx.accounts.prediction.bronze {
-            points += 1;
+        require!(
+            threshold > 0
+                && threshold as usize <= resolvers.len()
+                && resolvers.len() <= MAX_RESOLVERS,
+            PotreroError::InvalidQuorum
+        );
+        for (i, resolver) in resolvers.iter().enumerate() {
+            require!(
+                !resolvers[..i].contains(resolver),
+                PotreroError::InvalidQuorum
+            );
         }
-        ctx.accounts.player_points.score += points;
-        ctx.accounts.player_points.settled += 1;
-        ctx.accounts.oracle_event.settled += 1;
-
-        if ctx.accounts.oracle_event.leaderboard.len() < 10
-            || points
-                >= ctx.accounts.oracle_event.leaderboard
-                    [ctx.accounts.oracle_event.leaderboard.len() - 1]
-                    .score
-        {
-            if !ctx.accounts.oracle_event.leaderboard.is_empty()
-                && points
-                    > ctx.accounts.oracle_event.leaderboard
-                        [ctx.accounts.oracle_event.leaderboard.len() - 1]
-                        .score
-            {
-                if ctx.accounts.oracle_event.leaderboard
-                    [ctx.accounts.oracle_event.leaderboard.len() - 1]
-                    .score
-                    < points
-                    || ctx.accounts.oracle_event.leaderboard
-                        [ctx.accounts.oracle_event.leaderboard.len() - 1]
-                        .timestamp
-                        > ctx.accounts.prediction.timestamp
-                {
-                    ctx.accounts.oracle_event.leaderboard.pop();
-                }
-            }
-            let user_obj = Player {
-                pubkey: ctx.accounts.prediction.owner,
-                score: points,
-                timestamp: ctx.accounts.prediction.timestamp,
-            };
-
-            ctx.accounts.oracle_event.leaderboard.push(user_obj);
-            ctx.accounts
-                .oracle_event
-                .leaderboard
-                .sort_by(|a, b| match b.score.cmp(&a.score) {
-                    Ordering::Equal => b.timestamp.cmp(&a.timestamp),
-                    other => other,
-                });
-
+        ctx.accounts.results.resolvers = resolvers;
+        ctx.accounts.results.threshold = threshold;
+        Ok(())
+    }
 
-                let position = ctx
-                .accounts
-                .global
-                .leaderboard
-                .iter()
-                .position(|x| x.pubkey == ctx.accounts.prediction.owner);
-    
-            if position.is_some() {
-                let index = position.unwrap();
-                ctx.accounts.global.leaderboard[index].score = ctx.accounts.player_points.score;
-            } else if ctx.accounts.global.leaderboard.len() < 10
-                || ctx.accounts.player_points.score
-                    > ctx.accounts.global.leaderboard[ctx.accounts.global.leaderboard.len() - 1].score
-            {
-                if !ctx.accounts.global.leaderboard.is_empty()
-                    && ctx.accounts.player_points.score
-                        > ctx.accounts.global.leaderboard[ctx.accounts.global.leaderboard.len() - 1]
-                            .score
-                {
-                    ctx.accounts.global.leaderboard.pop();
-                }
-                let user_obj = Player {
-                    pubkey: ctx.accounts.prediction.owner,
-                    score: ctx.accounts.player_points.score,
-                    timestamp: ctx.accounts.player_points.timestamp,
-                };
-    
-                ctx.accounts.global.leaderboard.push(user_obj);
-                ctx.accounts
-                    .global
-                    .leaderboard
-                    .sort_by(|a, b| b.score.cmp(&a.score));
-            }
-            ctx.accounts
-                .global
-                .leaderboard
-                .sort_by(|a, b| b.score.cmp(&a.score));
+    pub fn cancel_event(ctx: Context<CancelEvent>, _name: String) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.resolution,
+            PotreroError::ResolutionPaused
+        );
+        require_keys_eq!(
+            ctx.accounts.resolver.key(),
+            ctx.accounts.oracle_event.resolver
+        );
+        ctx.accounts.oracle_event.ensure_active()?;
+        if ctx.accounts.oracle_event.solved_at != 0 {
+            return Err(PotreroError::EventClose.into());
         }
+        ctx.accounts.oracle_event.cancelled = true;
+        ctx.accounts.oracle_event.voided_at = Clock::get()?.unix_timestamp;
+        ctx.accounts.global.events -= 1;
+        Ok(())
+    }
 
+    pub fn abandon_event(ctx: Context<AbandonEvent>, _name: String) -> Result<()> {
+        // Resolvers can't resolve while resolution is paused, so the pause
+        // mustn't run out their clock either.
+        require!(
+            !ctx.accounts.config.pause.resolution,
+            PotreroError::ResolutionPaused
+        );
+        ctx.accounts.oracle_event.ensure_active()?;
+        if ctx.accounts.oracle_event.solved_at != 0 {
+            return Err(PotreroError::EventClose.into());
+        }
+        let clock = Clock::get()?;
+        if clock.unix_timestamp <= ctx.accounts.oracle_event.abandon_deadline() {
+            return Err(PotreroError::ResolverGracePeriod.into());
+        }
+        ctx.accounts.oracle_event.abandoned = true;
+        ctx.accounts.oracle_event.voided_at = clock.unix_timestamp;
+        ctx.accounts.global.events -= 1;
         Ok(())
     }
 
-    pub fn pay(ctx: Context<Pay>)-> Result<()> {
-        
-        require_eq!(ctx.accounts.oracle_event.payed, false);
-        require_eq!(ctx.accounts.oracle_event.settled, ctx.accounts.oracle_event.plays);
-        require_keys_eq!(
-            ctx.accounts.fee_receiver.key(),
-            ctx.accounts.oracle_event.fee_receiver
+    pub fn claim_refund(ctx: Context<ClaimRefund>, _name: String) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        require_keys_eq!(
-            ctx.accounts.winner_0.key(),
-            ctx.accounts.oracle_event.leaderboard[0].pubkey
+        require!(
+            ctx.accounts.oracle_event.is_void(),
+            PotreroError::EventNotCancelled
         );
         require_keys_eq!(
-            ctx.accounts.winner_1.key(),
-            ctx.accounts.oracle_event.leaderboard[1].pubkey
+            ctx.accounts.prediction.event,
+            ctx.accounts.oracle_event.key()
         );
-        require_keys_eq!(
-            ctx.accounts.winner_2.key(),
-            ctx.accounts.oracle_event.leaderboard[2].pubkey
+        require_keys_eq!(ctx.accounts.prediction.owner, ctx.accounts.player.key());
+        let accounts = ctx.accounts;
+        refund_entry(
+            &mut accounts.oracle_event,
+            &mut accounts.player_points,
+            &accounts.player.to_account_info(),
+            &accounts.token_program,
+            &accounts.mint,
+            &accounts.vault,
+            &accounts.player_token,
+        )
+    }
+
+    pub fn process_results(ctx: Context<ProcessResults>) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.processing,
+            PotreroError::ProcessingPaused
         );
         require_keys_eq!(
-            ctx.accounts.winner_3.key(),
-            ctx.accounts.oracle_event.leaderboard[3].pubkey
+            ctx.accounts.oracle_event.key(),
+            ctx.accounts.prediction.event
         );
-        require_keys_eq!(
-            ctx.accounts.winner_4.key(),
-            ctx.accounts.oracle_event.leaderboard[4].pubkey
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.oracle_event.ensure_processable(now)?;
+        require!(
+            !ctx.accounts.prediction.is_settled(),
+            PotreroError::PredictionSettled
         );
-        require_keys_eq!(
-            ctx.accounts.winner_5.key(),
-            ctx.accounts.oracle_event.leaderboard[5].pubkey
+        settle(
+            &mut ctx.accounts.oracle_event,
+            &mut ctx.accounts.global,
+            &mut ctx.accounts.prediction,
+            &mut ctx.accounts.player_points,
+            now,
         );
-        require_keys_eq!(
-            ctx.accounts.winner_6.key(),
-            ctx.accounts.oracle_event.leaderboard[6].pubkey
+        Ok(())
+    }
+
+    /// Settles `(prediction, player_points)` pairs passed as remaining
+    /// accounts. Settled predictions are skipped and the batch stops early
+    /// when compute runs low, so the same list can be resubmitted until
+    /// `settled == plays`.
+    pub fn process_results_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProcessResultsBatch<'info>>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.processing,
+            PotreroError::ProcessingPaused
         );
-        require_keys_eq!(
-            ctx.accounts.winner_7.key(),
-            ctx.accounts.oracle_event.leaderboard[7].pubkey
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.oracle_event.ensure_processable(now)?;
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(pairs.remainder().is_empty(), PotreroError::InvalidBatch);
+        let event = ctx.accounts.oracle_event.key();
+        for pair in pairs {
+            if sol_remaining_compute_units() < BATCH_COMPUTE_RESERVE {
+                break;
+            }
+            require!(
+                pair[0].is_writable && pair[1].is_writable,
+                PotreroError::InvalidBatch
+            );
+            let mut prediction = Account::<PodiumPrediction>::try_from(&pair[0])?;
+            let mut player_points = Account::<PlayerPoints>::try_from(&pair[1])?;
+            require_keys_eq!(prediction.event, event);
+            let owner = prediction.owner;
+            require_keys_eq!(
+                prediction.key(),
+                Pubkey::create_program_address(
+                    &[
+                        b"PodiumPrediction".as_ref(),
+                        event.as_ref(),
+                        owner.as_ref(),
+                        &[prediction.bump],
+                    ],
+                    &crate::ID,
+                )
+                .map_err(|_| PotreroError::InvalidBatch)?
+            );
+            require_keys_eq!(
+                player_points.key(),
+                Pubkey::create_program_address(
+                    &[
+                        b"points".as_ref(),
+                        ctx.accounts.oracle_event.season.as_ref(),
+                        owner.as_ref(),
+                        &[player_points.bump],
+                    ],
+                    &crate::ID,
+                )
+                .map_err(|_| PotreroError::InvalidBatch)?
+            );
+            if prediction.is_settled() {
+                continue;
+            }
+            settle(
+                &mut ctx.accounts.oracle_event,
+                &mut ctx.accounts.global,
+                &mut prediction,
+                &mut player_points,
+                now,
+            );
+            prediction.exit(&crate::ID)?;
+            player_points.exit(&crate::ID)?;
+        }
+        Ok(())
+    }
+
+    pub fn pay(ctx: Context<Pay>, _name: String) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        require_keys_eq!(
-            ctx.accounts.winner_8.key(),
-            ctx.accounts.oracle_event.leaderboard[8].pubkey
+        require_eq!(ctx.accounts.oracle_event.payed, false);
+        ctx.accounts.oracle_event.ensure_active()?;
+        require_eq!(
+            ctx.accounts.oracle_event.settled,
+            ctx.accounts.oracle_event.plays
         );
         require_keys_eq!(
-            ctx.accounts.winner_9.key(),
-            ctx.accounts.oracle_event.leaderboard[9].pubkey
+            ctx.accounts.fee_receiver.key(),
+            ctx.accounts.oracle_event.fee_receiver
         );
-        let lmps: u64 = ctx.accounts.oracle_event.to_account_info().lamports();
-
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.fee_receiver.clone(),
-            },
+        require_keys_eq!(
+            ctx.accounts.project_treasury.key(),
+            ctx.accounts.config.treasury
         );
-        system_program::transfer(cpi_context, lmps / 10_000 * ctx.accounts.oracle_event.fee)?;
+        let distribution = distribute(
+            ctx.accounts.oracle_event.pool,
+            &ctx.accounts.oracle_event.fees,
+            &ctx.accounts.oracle_event.payout,
+            ctx.accounts.oracle_event.leaderboard.len(),
+        )?;
+        let cuts = distribution
+            .creator
+            .checked_add(distribution.global)
+            .and_then(|cuts| cuts.checked_add(distribution.treasury))
+            .ok_or(PotreroError::MathOverflow)?;
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.global.to_account_info().clone(),
-            },
-        );
-        system_program::transfer(cpi_context, lmps / 100)?;
-
-        let remainder = lmps - lmps / 10_000 * ctx.accounts.oracle_event.fee - lmps / 100;
+        if ctx.accounts.oracle_event.is_token() {
+            let event = &ctx.accounts.oracle_event;
+            let bump = [event.bump];
+            let seeds = [b"OracleEvent".as_ref(), event.name.as_bytes(), &bump];
+            for (to, amount) in [
+                (&ctx.accounts.fee_receiver_token, distribution.creator),
+                (&ctx.accounts.treasury_token, distribution.treasury),
+                (&ctx.accounts.global_vault, distribution.global),
+            ] {
+                transfer_tokens(
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.mint,
+                    &ctx.accounts.vault,
+                    to,
+                    event.to_account_info(),
+                    &[&seeds],
+                    amount,
+                )?;
+            }
+            let mint = event.mint;
+            ctx.accounts
+                .global
+                .add_to_token_pool(mint, distribution.global)?;
+        } else {
+            ctx.accounts.oracle_event.sub_lamports(cuts)?;
+            ctx.accounts
+                .fee_receiver
+                .add_lamports(distribution.creator)?;
+            ctx.accounts
+                .project_treasury
+                .add_lamports(distribution.treasury)?;
+            ctx.accounts.global.add_lamports(distribution.global)?;
+            ctx.accounts.global.pool = ctx
+                .accounts
+                .global
+                .pool
+                .checked_add(distribution.global)
+                .ok_or(PotreroError::MathOverflow)?;
+        }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_0.clone(),
-            },
-        );
-        system_program::transfer(cpi_context, remainder / 100 * 35)?;
+        ctx.accounts.oracle_event.prizes =
+            prizes_for(&ctx.accounts.oracle_event.leaderboard, &distribution.prizes);
+        ctx.accounts.oracle_event.payed = true;
+        ctx.accounts.oracle_event.payed_at = Clock::get()?.unix_timestamp;
+        ctx.accounts.global.settled += 1;
+        Ok(())
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_1.clone(),
-            },
+    pub fn claim_prize(ctx: Context<ClaimPrize>, _name: String) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        system_program::transfer(cpi_context, remainder / 100 * 25)?;
+        require!(ctx.accounts.oracle_event.payed, PotreroError::EventNotPayed);
+        let amount = claim(
+            &mut ctx.accounts.oracle_event.prizes,
+            ctx.accounts.winner.key(),
+        )?;
+        if ctx.accounts.oracle_event.is_token() {
+            let event = &ctx.accounts.oracle_event;
+            let bump = [event.bump];
+            let seeds = [b"OracleEvent".as_ref(), event.name.as_bytes(), &bump];
+            return transfer_tokens(
+                &ctx.accounts.token_program,
+                &ctx.accounts.mint,
+                &ctx.accounts.vault,
+                &ctx.accounts.winner_token,
+                event.to_account_info(),
+                &[&seeds],
+                amount,
+            );
+        }
+        ctx.accounts.oracle_event.sub_lamports(amount)?;
+        ctx.accounts.winner.add_lamports(amount)?;
+        Ok(())
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_2.clone(),
-            },
+    pub fn pay_global(ctx: Context<PayGlobal>) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        system_program::transfer(cpi_context, remainder / 100 * 15)?;
-
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_3.clone(),
-            },
+        require_eq!(ctx.accounts.global.payed, false);
+        require!(!ctx.accounts.global.open, PotreroError::SeasonOpen);
+        require_eq!(ctx.accounts.global.settled, ctx.accounts.global.events);
+        require_keys_eq!(
+            ctx.accounts.project_treasury.key(),
+            ctx.accounts.config.treasury
         );
-        system_program::transfer(cpi_context, remainder / 100 * 8)?;
+        let amounts = split_pool(
+            ctx.accounts.global.pool,
+            &ctx.accounts.global.payout,
+            ctx.accounts.global.leaderboard.len(),
+        )?;
+        ctx.accounts.global.prizes = prizes_for(&ctx.accounts.global.leaderboard, &amounts);
+        ctx.accounts.global.payed = true;
+        ctx.accounts.global.payed_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_4.clone(),
-            },
-        );
-        system_program::transfer(cpi_context, remainder / 100 * 5)?;
+    pub fn set_global_payout(ctx: Context<SetGlobalPayout>, payout: Vec<u16>) -> Result<()> {
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        require_eq!(ctx.accounts.global.payed, false);
+        validate_payout(&payout)?;
+        ctx.accounts.global.payout = payout;
+        Ok(())
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_5.clone(),
-            },
+    pub fn claim_global_prize(ctx: Context<ClaimGlobalPrize>) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        system_program::transfer(cpi_context, remainder / 100 * 4)?;
+        require!(ctx.accounts.global.payed, PotreroError::EventNotPayed);
+        let amount = claim(&mut ctx.accounts.global.prizes, ctx.accounts.winner.key())?;
+        ctx.accounts.global.sub_lamports(amount)?;
+        ctx.accounts.winner.add_lamports(amount)?;
+        Ok(())
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_6.clone(),
-            },
+    /// Pays a closed season's pool through a Merkle root of
+    /// `(index, player, amount)` leaves instead of the ten-place leaderboard,
+    /// so any number of players can be paid. `MerkleTree` builds the root
+    /// from the season's `PlayerPoints`.
+    pub fn post_distribution(
+        ctx: Context<PostDistribution>,
+        root: [u8; 32],
+        total: u64,
+        claimants: u32,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        system_program::transfer(cpi_context, remainder / 100 * 3)?;
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        let global = &mut ctx.accounts.global;
+        require_eq!(global.payed, false);
+        require!(!global.open, PotreroError::SeasonOpen);
+        require_eq!(global.settled, global.events);
+        require!(total <= global.pool, PotreroError::InvalidDistribution);
+        global.payed = true;
+        global.payed_at = Clock::get()?.unix_timestamp;
+        ctx.accounts.distribution.set_inner(MerkleDistribution {
+            season: global.key(),
+            root,
+            total,
+            claimed: 0,
+            claimants,
+            bitmap: vec![0; MerkleDistribution::bitmap_len(claimants)],
+            bump: ctx.bumps.distribution,
+        });
+        Ok(())
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_7.clone(),
-            },
+    pub fn claim_distribution(
+        ctx: Context<ClaimDistribution>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        system_program::transfer(cpi_context, remainder / 100 * 2)?;
+        ctx.accounts
+            .distribution
+            .claim(index, &ctx.accounts.winner.key(), amount, &proof)?;
+        ctx.accounts.global.sub_lamports(amount)?;
+        ctx.accounts.winner.add_lamports(amount)?;
+        Ok(())
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_8.clone(),
-            },
+    /// Pays one of a season's token pools through its own Merkle root, the
+    /// way `post_distribution` pays the SOL pool. It can only follow
+    /// `post_distribution`, since a season paid by leaderboard pays its
+    /// token pools through `claim_global_token_prize`.
+    pub fn post_token_distribution(
+        ctx: Context<PostTokenDistribution>,
+        root: [u8; 32],
+        total: u64,
+        claimants: u32,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        system_program::transfer(cpi_context, remainder / 100 * 2)?;
-
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.oracle_event.to_account_info().clone(),
-                to: ctx.accounts.winner_9.clone(),
-            },
+        require_keys_eq!(ctx.accounts.authority.key(), ctx.accounts.config.authority);
+        let season = ctx.accounts.global.key();
+        let token_pool = ctx
+            .accounts
+            .global
+            .token_pools
+            .iter_mut()
+            .find(|token_pool| token_pool.mint == ctx.accounts.mint.key())
+            .ok_or(PotreroError::NoPrize)?;
+        require!(!token_pool.distributed, PotreroError::InvalidDistribution);
+        require!(
+            total <= token_pool.amount,
+            PotreroError::InvalidDistribution
         );
-        system_program::transfer(cpi_context, remainder / 100 * 1)?;
-
-        ctx.accounts.oracle_event.payed = true;
-        ctx.accounts.global.settled += 1;
-    Ok(())
+        token_pool.distributed = true;
+        ctx.accounts.distribution.set_inner(MerkleDistribution {
+            season,
+            root,
+            total,
+            claimed: 0,
+            claimants,
+            bitmap: vec![0; MerkleDistribution::bitmap_len(claimants)],
+            bump: ctx.bumps.distribution,
+        });
+        Ok(())
     }
-    pub fn pay_global(ctx: Context<PayGlobal>)-> Result<()> {
-        require_eq!(ctx.accounts.global.settled, ctx.accounts.global.events);
-        require_keys_eq!(
-            ctx.accounts.project_treasury.key(),
-            Pubkey::from_str(PROJECT_TREASURY).unwrap()
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_0.key(),
-            ctx.accounts.global.leaderboard[0].pubkey
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_1.key(),
-            ctx.accounts.global.leaderboard[1].pubkey
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_2.key(),
-            ctx.accounts.global.leaderboard[2].pubkey
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_3.key(),
-            ctx.accounts.global.leaderboard[3].pubkey
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_4.key(),
-            ctx.accounts.global.leaderboard[4].pubkey
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_5.key(),
-            ctx.accounts.global.leaderboard[5].pubkey
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_6.key(),
-            ctx.accounts.global.leaderboard[6].pubkey
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_7.key(),
-            ctx.accounts.global.leaderboard[7].pubkey
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_8.key(),
-            ctx.accounts.global.leaderboard[8].pubkey
-        );
-        require_keys_eq!(
-            ctx.accounts.winner_9.key(),
-            ctx.accounts.global.leaderboard[9].pubkey
-        );
-        let lmps: u64 = ctx.accounts.global.to_account_info().lamports();
-
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_0.clone(),
-            },
-        );
-        system_program::transfer(cpi_context, lmps / 100 * 35)?;
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_1.clone(),
-            },
+    pub fn claim_token_distribution(
+        ctx: Context<ClaimTokenDistribution>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        system_program::transfer(cpi_context, lmps / 100 * 25)?;
+        ctx.accounts
+            .distribution
+            .claim(index, &ctx.accounts.winner.key(), amount, &proof)?;
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_2.clone(),
+        let global = &ctx.accounts.global;
+        let bump = [global.bump];
+        let seeds = [b"season".as_ref(), global.name.as_bytes(), &bump];
+        let signer_seeds = [seeds.as_ref()];
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.global_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.winner_token.to_account_info(),
+                authority: ctx.accounts.global.to_account_info(),
             },
+            &signer_seeds,
         );
-        system_program::transfer(cpi_context, lmps / 100 * 15)?;
+        token_interface::transfer_checked(cpi_context, amount, ctx.accounts.mint.decimals)
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_3.clone(),
-            },
+    pub fn claim_global_token_prize(ctx: Context<ClaimGlobalTokenPrize>) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        system_program::transfer(cpi_context, lmps / 100 * 8)?;
-
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_4.clone(),
-            },
+        require!(ctx.accounts.global.payed, PotreroError::EventNotPayed);
+        let global = &mut ctx.accounts.global;
+        let place = global
+            .prizes
+            .iter()
+            .position(|prize| prize.winner == ctx.accounts.winner.key())
+            .ok_or(PotreroError::NoPrize)?;
+        let winners = global.prizes.len();
+        let payout = global.payout.clone();
+        let token_pool = global
+            .token_pools
+            .iter_mut()
+            .find(|token_pool| token_pool.mint == ctx.accounts.mint.key())
+            .ok_or(PotreroError::NoPrize)?;
+        require!(
+            token_pool.claimed & (1 << place) == 0,
+            PotreroError::NoPrize
         );
-        system_program::transfer(cpi_context, lmps / 100 * 5)?;
+        token_pool.claimed |= 1 << place;
+        let amount = split_pool(token_pool.amount, &payout, winners)?[place];
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_5.clone(),
+        let global = &ctx.accounts.global;
+        let bump = [global.bump];
+        let seeds = [b"season".as_ref(), global.name.as_bytes(), &bump];
+        let signer_seeds = [seeds.as_ref()];
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.global_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.winner_token.to_account_info(),
+                authority: ctx.accounts.global.to_account_info(),
             },
+            &signer_seeds,
         );
-        system_program::transfer(cpi_context, lmps / 100 * 4)?;
+        token_interface::transfer_checked(cpi_context, amount, ctx.accounts.mint.decimals)
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_6.clone(),
-            },
+    /// Lets a player take back the rent of their prediction once it's
+    /// settled and the event has paid out.
+    pub fn close_prediction_pda(ctx: Context<ClosePredictionPda>) -> Result<()> {
+        require!(ctx.accounts.oracle_event.payed, PotreroError::EventClose);
+        require_keys_eq!(
+            ctx.accounts.prediction.event,
+            ctx.accounts.oracle_event.key()
         );
-        system_program::transfer(cpi_context, lmps / 100 * 3)?;
-
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_7.clone(),
-            },
+        require!(
+            ctx.accounts.prediction.is_settled(),
+            PotreroError::PredictionNotSettled
         );
-        system_program::transfer(cpi_context, lmps / 100 * 2)?;
+        ctx.accounts.oracle_event.closed += 1;
+        Ok(())
+    }
 
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_8.clone(),
-            },
+    /// Cleanup crank for predictions their players left open. Once the
+    /// retention period is over anyone can close a settled prediction.
+    pub fn clean_prediction(ctx: Context<CleanPrediction>) -> Result<()> {
+        let event = &mut ctx.accounts.oracle_event;
+        require!(event.payed, PotreroError::EventNotPayed);
+        require!(
+            Clock::get()?.unix_timestamp >= event.payed_at + CLEANUP_RETENTION,
+            PotreroError::RetentionPeriod
         );
-        system_program::transfer(cpi_context, lmps / 100 * 2)?;
-
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.global.to_account_info().clone(),
-                to: ctx.accounts.winner_9.clone(),
-            },
+        require!(
+            ctx.accounts.prediction.is_settled(),
+            PotreroError::PredictionNotSettled
         );
-        system_program::transfer(cpi_context, lmps / 100 * 1)?;
-    Ok(())
-
-    } 
+        event.closed += 1;
+        refund_cleanup(
+            &ctx.accounts.prediction.to_account_info(),
+            &ctx.accounts.owner,
+        )
+    }
 
-    pub fn close_prediction_pda(ctx: Context<ClosePredictionPda>) -> Result<()> {
-        require_keys_eq!(
-            ctx.accounts.project_treasury.key(),
-            Pubkey::from_str(PROJECT_TREASURY).unwrap()
+    /// Cleanup crank for void events whose players never claimed their
+    /// refund. Once the retention period is over anyone can refund the
+    /// entry fee to the prediction's owner and close it.
+    pub fn clean_refund(ctx: Context<CleanRefund>) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
-        require!( ctx.accounts.oracle_event.payed, PotreroError::EventClose);
-        require_keys_eq!(
-            ctx.accounts.prediction.event,
-            ctx.accounts.oracle_event.key()
+        let event = &ctx.accounts.oracle_event;
+        require!(event.is_void(), PotreroError::EventNotCancelled);
+        require!(
+            Clock::get()?.unix_timestamp >= event.voided_at + CLEANUP_RETENTION,
+            PotreroError::RetentionPeriod
         );
-        Ok(())
+        let accounts = ctx.accounts;
+        refund_entry(
+            &mut accounts.oracle_event,
+            &mut accounts.player_points,
+            &accounts.owner,
+            &accounts.token_program,
+            &accounts.mint,
+            &accounts.vault,
+            &accounts.owner_token,
+        )?;
+        refund_cleanup(&accounts.prediction.to_account_info(), &accounts.owner)
     }
 
+    /// Cleanup crank for finished events: anyone can close the event and
+    /// its results once `ensure_cleanable` passes. A token event's vault is
+    /// closed too, its rent and any leftover tokens going to the maker.
     pub fn close_event_pdas(ctx: Context<CloseEventPda>) -> Result<()> {
-        require_keys_eq!(
-            ctx.accounts.project_treasury.key(),
-            Pubkey::from_str(PROJECT_TREASURY).unwrap()
-        );
-        require_eq!(
-            ctx.accounts.global.events,
-            ctx.accounts.global.settled
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
         );
+        let event = &ctx.accounts.oracle_event;
+        event.ensure_cleanable(Clock::get()?.unix_timestamp)?;
+        if event.is_token() {
+            let (Some(token_program), Some(vault)) =
+                (&ctx.accounts.token_program, &ctx.accounts.vault)
+            else {
+                return Err(PotreroError::MissingTokenAccount.into());
+            };
+            let bump = [event.bump];
+            let seeds = [b"OracleEvent".as_ref(), event.name.as_bytes(), &bump];
+            // Refunds of a void event leave its withdraw penalties behind.
+            if vault.amount > 0 {
+                transfer_tokens(
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.mint,
+                    &ctx.accounts.vault,
+                    &ctx.accounts.maker_token,
+                    event.to_account_info(),
+                    &[&seeds],
+                    vault.amount,
+                )?;
+            }
+            token_interface::close_account(CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                CloseAccount {
+                    account: vault.to_account_info(),
+                    destination: ctx.accounts.maker.to_account_info(),
+                    authority: event.to_account_info(),
+                },
+                &[&seeds],
+            ))?;
+        }
+        refund_cleanup(
+            &ctx.accounts.oracle_event.to_account_info(),
+            &ctx.accounts.maker,
+        )?;
+        refund_cleanup(&ctx.accounts.results.to_account_info(), &ctx.accounts.maker)
+    }
 
+    /// Lets a player take back the rent of their season points once the
+    /// season is paid and none of their entries still awaits a refund.
+    pub fn close_player_points(ctx: Context<ClosePlayerPoints>) -> Result<()> {
+        let global = &mut ctx.accounts.global;
+        require!(global.payed, PotreroError::EventNotPayed);
+        ctx.accounts.player_points.ensure_closable()?;
+        global.players -= 1;
         Ok(())
     }
 
-    pub fn close_leaderboard_pdas(ctx: Context<CloseLeaderboardPda>) -> Result<()> {
-        require_keys_eq!(
-            ctx.accounts.project_treasury.key(),
-            Pubkey::from_str(PROJECT_TREASURY).unwrap()
+    /// Cleanup crank for season points their players left open, once the
+    /// retention period after the season's payout is over.
+    pub fn clean_player_points(ctx: Context<CleanPlayerPoints>) -> Result<()> {
+        let global = &mut ctx.accounts.global;
+        require!(global.payed, PotreroError::EventNotPayed);
+        require!(
+            Clock::get()?.unix_timestamp >= global.payed_at + CLEANUP_RETENTION,
+            PotreroError::RetentionPeriod
         );
-        require_eq!(
-            ctx.accounts.global.events,
-            ctx.accounts.global.settled
+        ctx.accounts.player_points.ensure_closable()?;
+        global.players -= 1;
+        refund_cleanup(
+            &ctx.accounts.player_points.to_account_info(),
+            &ctx.accounts.owner,
+        )
+    }
+
+    /// Closes a paid season once every prize is claimed and every player's
+    /// points are closed, along with its rankings and Merkle distributions.
+    /// Points are found by the season's address, so until they are all gone
+    /// the name can't be reopened. The season's rent goes back to whoever opened
+    /// it and anything left above it to the treasury. Token distributions
+    /// are passed as remaining accounts, one per `distributed` pool.
+    pub fn close_leaderboard_pdas<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseLeaderboardPda<'info>>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.config.pause.payouts,
+            PotreroError::PayoutsPaused
+        );
+        let global = &ctx.accounts.global;
+        require!(!global.open, PotreroError::SeasonOpen);
+        require!(global.payed, PotreroError::EventNotPayed);
+        require_eq!(global.events, global.settled);
+        require!(
+            global.prizes.iter().all(|prize| prize.claimed),
+            PotreroError::UnclaimedPrizes
         );
+        require_eq!(global.players, 0, PotreroError::OpenPlayerPoints);
+        let places = global.prizes.len();
+        let mut token_distributions = ctx.remaining_accounts.iter();
+        for token_pool in &global.token_pools {
+            if !token_pool.distributed {
+                require!(
+                    token_pool.amount == 0
+                        || (places > 0 && token_pool.claimed == (1 << places) - 1),
+                    PotreroError::UnclaimedPrizes
+                );
+                continue;
+            }
+            let distribution = token_distributions
+                .next()
+                .ok_or(PotreroError::UnclaimedPrizes)?;
+            let (address, _) = Pubkey::find_program_address(
+                &[
+                    b"distribution",
+                    global.key().as_ref(),
+                    token_pool.mint.as_ref(),
+                ],
+                &crate::ID,
+            );
+            require_keys_eq!(distribution.key(), address);
+            close_distribution(distribution, &ctx.accounts.opened_by)?;
+        }
+        if *ctx.accounts.distribution.owner == crate::ID {
+            close_distribution(&ctx.accounts.distribution, &ctx.accounts.opened_by)?;
+        }
+        if *ctx.accounts.rankings.owner == crate::ID {
+            close_program_account(&ctx.accounts.rankings, &ctx.accounts.opened_by)?;
+        }
 
+        let global = ctx.accounts.global.to_account_info();
+        let rent = Rent::get()?.minimum_balance(global.data_len());
+        let leftover = global.lamports().saturating_sub(rent);
+        global.sub_lamports(leftover)?;
+        ctx.accounts.project_treasury.add_lamports(leftover)?;
         Ok(())
     }
 }
 
-
 #[derive(Accounts)]
-#[instruction(name: String)]
-pub struct Initialize<'info> {
+pub struct InitConfig<'info> {
     #[account(mut)]
-    pub maker: Signer<'info>,
+    pub authority: Signer<'info>,
     #[account(
         init,
-        payer = maker,
-        seeds = [b"OracleEvent".as_ref(), name.as_ref()],
+        payer = authority,
+        seeds = [b"config".as_ref()],
         bump,
-        space = 1000,
+        space = 8 + Config::INIT_SPACE,
     )]
-    pub oracle_event: Account<'info, OracleEvent>,
+    pub config: Account<'info, Config>,
+    pub program: Program<'info, crate::program::PotreroOlimpcs>,
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
     #[account(
-        init_if_needed,
-        payer = maker,
-        seeds = [b"olimpics".as_ref()],
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub pending_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct OpenSeason<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"season".as_ref(), name.as_ref()],
         bump,
         space = Leaderboard::LEN,
     )]
@@ -590,82 +1515,191 @@ pub struct Initialize<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(_name: String)]
-pub struct MakePrediction<'info> {
-    #[account(mut)]
-    pub player: Signer<'info>,
+pub struct CloseSeason<'info> {
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
     #[account(
         mut,
-        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
-        bump = oracle_event.bump
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
     )]
-    pub oracle_event: Account<'info, OracleEvent>,
+    pub global: Account<'info, Leaderboard>,
+}
+
+#[derive(Accounts)]
+pub struct OpenRankings<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
     #[account(
-        init,
-        payer = player,
-        seeds = [b"PodiumPrediction".as_ref(), _name.as_ref(), oracle_event.plays.to_le_bytes().as_ref()],
-        bump,
-        space =  8 + PodiumPrediction::INIT_SPACE,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
     )]
-    pub prediction: Account<'info, PodiumPrediction>,
+    pub global: Account<'info, Leaderboard>,
     #[account(
         init,
-        payer = player,
-        seeds = [b"lock".as_ref(),  _name.as_ref(), player.key().as_ref(),],
-        bump,
-        space =  8 + 1,
-    )]
-    pub lock: Account<'info, Lock>,
-    #[account(
-        init_if_needed,
-        payer = player,
-        seeds = [b"olimpics".as_ref(), player.key().as_ref(),],
+        payer = authority,
+        seeds = [b"rankings".as_ref(), global.key().as_ref()],
         bump,
-        space =  8 + PlayerPoints::INIT_SPACE,
+        space = Rankings::SPACE,
     )]
-    pub player_points: Account<'info, PlayerPoints>,
-    /// CHECK: This should match the fee_receiver in the OracleEvent
-    #[account(mut)]
-    pub fee_receiver: AccountInfo<'info>,
+    pub rankings: AccountLoader<'info, Rankings>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ProcessResults<'info> {
+pub struct GrowRankings<'info> {
     #[account(mut)]
-    pub signer: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
     #[account(
-        mut,
-        seeds = [b"OracleEvent".as_ref(), oracle_event.name.as_ref()],
-        bump = oracle_event.bump
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
     )]
-    pub oracle_event: Account<'info, OracleEvent>,
-    #[account(mut)]
-    pub prediction: Account<'info, PodiumPrediction>,
+    pub global: Account<'info, Leaderboard>,
     #[account(
         mut,
-        close = project_treasury,
-        seeds = [b"lock".as_ref(),  oracle_event.name.as_ref(), prediction.owner.as_ref(),],
-        bump = lock.bump
+        seeds = [b"rankings".as_ref(), global.key().as_ref()],
+        bump = rankings.load()?.bump,
+        realloc = rankings.to_account_info().data_len() + Rankings::PAGE_SPACE,
+        realloc::payer = authority,
+        realloc::zero = false,
     )]
-    pub lock: Account<'info, Lock>,
+    pub rankings: AccountLoader<'info, Rankings>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RankPlayer<'info> {
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    #[account(
+        mut,
+        seeds = [b"points".as_ref(), global.key().as_ref(), player_points.pubkey.as_ref()],
+        bump = player_points.bump
+    )]
+    pub player_points: Account<'info, PlayerPoints>,
     #[account(
         mut,
-        seeds = [b"olimpics".as_ref(), prediction.owner.key().as_ref(),],
+        seeds = [b"rankings".as_ref(), global.key().as_ref()],
+        bump = rankings.load()?.bump
+    )]
+    pub rankings: AccountLoader<'info, Rankings>,
+}
+
+#[derive(Accounts)]
+pub struct PlayerRank<'info> {
+    #[account(
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    #[account(
+        seeds = [b"points".as_ref(), global.key().as_ref(), player_points.pubkey.as_ref()],
         bump = player_points.bump
     )]
     pub player_points: Account<'info, PlayerPoints>,
-    /// CHECK: This should match a constant Pubkey in the program
+    #[account(
+        seeds = [b"rankings".as_ref(), global.key().as_ref()],
+        bump = rankings.load()?.bump
+    )]
+    pub rankings: AccountLoader<'info, Rankings>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct ApproveCreator<'info> {
     #[account(mut)]
-    pub project_treasury: AccountInfo<'info>,
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"creator".as_ref(), creator.as_ref()],
+        bump,
+        space = 8 + CreatorApproval::INIT_SPACE,
+    )]
+    pub approval: Account<'info, CreatorApproval>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_creator: Pubkey)]
+pub struct RevokeCreator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"creator".as_ref(), _creator.as_ref()],
+        bump = approval.bump
+    )]
+    pub approval: Account<'info, CreatorApproval>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String, positions: u8)]
+pub struct Initialize<'info> {
     #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"OracleEvent".as_ref(), name.as_ref()],
+        bump,
+        space = OracleEvent::space(positions),
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"EventResults".as_ref(), name.as_ref()],
+        bump,
+        space = EventResults::space(positions),
+    )]
+    pub results: Account<'info, EventResults>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
     pub global: Account<'info, Leaderboard>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [b"creator".as_ref(), maker.key().as_ref()],
+        bump = approval.bump
+    )]
+    pub approval: Account<'info, CreatorApproval>,
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"vault".as_ref(), oracle_event.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = oracle_event,
+        token::token_program = token_program,
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(_name: String)]
-pub struct Pay<'info> {
+pub struct MakePrediction<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
     #[account(
@@ -674,174 +1708,1253 @@ pub struct Pay<'info> {
         bump = oracle_event.bump
     )]
     pub oracle_event: Account<'info, OracleEvent>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_0: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_1: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_2: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_3: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_4: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_5: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_6: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_7: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_8: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_9: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = player,
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), player.key().as_ref()],
+        bump,
+        space = PodiumPrediction::space(oracle_event.positions),
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        seeds = [b"points".as_ref(), oracle_event.season.as_ref(), player.key().as_ref()],
+        bump,
+        space =  8 + PlayerPoints::INIT_SPACE,
+    )]
+    pub player_points: Account<'info, PlayerPoints>,
+    #[account(mut, address = oracle_event.season)]
+    pub global: Account<'info, Leaderboard>,
     /// CHECK: This should match the fee_receiver in the OracleEvent
     #[account(mut)]
     pub fee_receiver: AccountInfo<'info>,
-    #[account(mut)]
-    pub global: Account<'info, Leaderboard>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = oracle_event.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut, token::mint = mint, token::authority = player)]
+    pub player_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), oracle_event.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(_name: String)]
-pub struct PayGlobal<'info> {
-    #[account(mut)]
+pub struct RevealPrediction<'info> {
     pub player: Signer<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_0: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_1: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_2: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_3: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_4: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_5: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_6: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_7: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_8: AccountInfo<'info>,
-    /// CHECK: This should match the leaderboard in the OracleEvent
-    #[account(mut)]
-    pub winner_9: AccountInfo<'info>,
-    /// CHECK: This should match a constant Pubkey in the program
-    #[account(mut)]
-    pub project_treasury: AccountInfo<'info>,
-    #[account(mut)]
-    pub global: Account<'info, Leaderboard>,
-    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), player.key().as_ref()],
+        bump = prediction.bump
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
 }
 
 #[derive(Accounts)]
-pub struct ClosePredictionPda<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
+#[instruction(_name: String)]
+pub struct UpdatePrediction<'info> {
+    pub player: Signer<'info>,
     #[account(
-        mut
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
     )]
     pub oracle_event: Account<'info, OracleEvent>,
     #[account(
         mut,
-        close = project_treasury
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), player.key().as_ref()],
+        bump = prediction.bump
     )]
     pub prediction: Account<'info, PodiumPrediction>,
-    /// CHECK: This should match a constant Pubkey in the program
-    #[account(mut)]
-    pub project_treasury: AccountInfo<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
 }
 
 #[derive(Accounts)]
-pub struct CloseEventPda<'info> {
+#[instruction(_name: String)]
+pub struct WithdrawPrediction<'info> {
     #[account(mut)]
-    pub signer: Signer<'info>,
+    pub player: Signer<'info>,
     #[account(
         mut,
-        close = project_treasury
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
     )]
     pub oracle_event: Account<'info, OracleEvent>,
-    #[account(mut)]
-    pub global: Account<'info, Leaderboard>,
-    #[account(mut)]
-    pub project_treasury: AccountInfo<'info>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), player.key().as_ref()],
+        bump = prediction.bump
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+    #[account(
+        mut,
+        seeds = [b"points".as_ref(), oracle_event.season.as_ref(), player.key().as_ref()],
+        bump = player_points.bump
+    )]
+    pub player_points: Account<'info, PlayerPoints>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = oracle_event.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut, token::mint = mint, token::authority = player)]
+    pub player_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), oracle_event.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CloseLeaderboardPda<'info> {
+#[instruction(_name: String)]
+pub struct MigrateEvent<'info> {
     #[account(mut)]
-    pub signer: Signer<'info>,
-    #[account(mut, close = project_treasury)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
     pub global: Account<'info, Leaderboard>,
-    #[account(mut)]
-    pub project_treasury: AccountInfo<'info>,
+    /// CHECK: Parsed as a LegacyOracleEvent and rewritten in the current layout
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump
+    )]
+    pub oracle_event: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"EventResults".as_ref(), _name.as_ref()],
+        bump,
+        space = EventResults::space(3),
+    )]
+    pub results: Account<'info, EventResults>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 #[instruction(name: String)]
-pub struct OracleResolve<'info> {
+pub struct MigratePrediction<'info> {
     #[account(mut)]
-    pub resolver: Signer<'info>,
+    pub cranker: Signer<'info>,
+    /// CHECK: Receives the old accounts' rent, checked against the legacy prediction's owner
     #[account(mut)]
+    pub player: AccountInfo<'info>,
+    #[account(
+        seeds = [b"OracleEvent".as_ref(), name.as_ref()],
+        bump = oracle_event.bump
+    )]
     pub oracle_event: Account<'info, OracleEvent>,
+    /// CHECK: Parsed as a LegacyPrediction and checked against its old seeds, the player and event
+    #[account(mut)]
+    pub legacy_prediction: AccountInfo<'info>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"lock".as_ref(),  name.as_ref(), player.key().as_ref(),],
+        bump = lock.bump
+    )]
+    pub lock: Account<'info, Lock>,
+    #[account(
+        init,
+        payer = cranker,
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), player.key().as_ref()],
+        bump,
+        space = PodiumPrediction::space(oracle_event.positions),
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        seeds = [b"points".as_ref(), oracle_event.season.as_ref(), player.key().as_ref()],
+        bump,
+        space = 8 + PlayerPoints::INIT_SPACE,
+    )]
+    pub player_points: Account<'info, PlayerPoints>,
+    #[account(mut, address = oracle_event.season)]
+    pub global: Account<'info, Leaderboard>,
     pub system_program: Program<'info, System>,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct PodiumPrediction {
-    pub gold: u8,
-    pub silver: u8,
-    pub bronze: u8,
-    pub event: Pubkey,
-    pub timestamp: i64,
-    pub owner: Pubkey,
-    pub bump: u8,
+#[derive(Accounts)]
+#[instruction(_name: String)]
+pub struct OpenDispute<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), player.key().as_ref()],
+        bump = prediction.bump
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+    #[account(
+        init,
+        payer = player,
+        seeds = [b"Dispute".as_ref(), _name.as_ref(), player.key().as_ref(),],
+        bump,
+        space = 8 + Dispute::INIT_SPACE,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    pub system_program: Program<'info, System>,
 }
 
-#[account]
-pub struct OracleEvent {
-    pub open_until_time: i64,
-    pub resolultion_time: i64,
-    pub solved_at: i64,
-    pub resolver: Pubkey,
-    pub fee: u64,
-    pub creator_basis_points: u16,
-    pub fee_receiver: Pubkey,
-    pub plays: u128,
-    pub settled: u128,
-    pub payed: bool,
-    pub bump: u8,
-    pub gold: u8,
-    pub silver: u8,
-    pub bronze: u8,
-    pub leaderboard: Vec<Player>,
-    pub name: String,
+#[derive(Accounts)]
+#[instruction(_name: String)]
+pub struct AmendResults<'info> {
+    pub arbitrator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"EventResults".as_ref(), _name.as_ref()],
+        bump = results.bump
+    )]
+    pub results: Account<'info, EventResults>,
 }
 
-#[account]
-pub struct Lock {
+#[derive(Accounts)]
+#[instruction(_name: String)]
+pub struct SettleDispute<'info> {
+    pub arbitrator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"Dispute".as_ref(), _name.as_ref(), player.key().as_ref(),],
+        bump = dispute.bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    /// CHECK: This should match the player stored in the Dispute
+    #[account(mut, address = dispute.player)]
+    pub player: AccountInfo<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: This should match the treasury in the Config
+    #[account(mut)]
+    pub project_treasury: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_name: String)]
+pub struct SetResolvers<'info> {
+    pub resolver: Signer<'info>,
+    #[account(
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        seeds = [b"EventResults".as_ref(), _name.as_ref()],
+        bump = results.bump
+    )]
+    pub results: Account<'info, EventResults>,
+}
+
+#[derive(Accounts)]
+#[instruction(_name: String)]
+pub struct CancelEvent<'info> {
+    pub resolver: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(mut, address = oracle_event.season)]
+    pub global: Account<'info, Leaderboard>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(_name: String)]
+pub struct AbandonEvent<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(mut, address = oracle_event.season)]
+    pub global: Account<'info, Leaderboard>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+#[instruction(_name: String)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), player.key().as_ref()],
+        bump = prediction.bump
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+    #[account(
+        mut,
+        seeds = [b"points".as_ref(), oracle_event.season.as_ref(), player.key().as_ref()],
+        bump = player_points.bump
+    )]
+    pub player_points: Account<'info, PlayerPoints>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = oracle_event.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut, token::mint = mint, token::authority = player)]
+    pub player_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), oracle_event.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProcessResults<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), oracle_event.name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), prediction.owner.as_ref()],
+        bump = prediction.bump
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+    #[account(
+        mut,
+        seeds = [b"points".as_ref(), oracle_event.season.as_ref(), prediction.owner.as_ref()],
+        bump = player_points.bump
+    )]
+    pub player_points: Account<'info, PlayerPoints>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, address = oracle_event.season)]
+    pub global: Account<'info, Leaderboard>,
+}
+
+#[derive(Accounts)]
+pub struct ProcessResultsBatch<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), oracle_event.name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(mut, address = oracle_event.season)]
+    pub global: Account<'info, Leaderboard>,
+}
+
+#[derive(Accounts)]
+#[instruction(_name: String)]
+pub struct Pay<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    /// CHECK: This should match the fee_receiver in the OracleEvent
+    #[account(mut)]
+    pub fee_receiver: AccountInfo<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: This should match the treasury in the Config
+    #[account(mut)]
+    pub project_treasury: AccountInfo<'info>,
+    #[account(mut, address = oracle_event.season)]
+    pub global: Account<'info, Leaderboard>,
+    #[account(address = oracle_event.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), oracle_event.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(mut, token::mint = mint, token::authority = fee_receiver)]
+    pub fee_receiver_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(mut, token::mint = mint, token::authority = project_treasury)]
+    pub treasury_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        seeds = [b"vault".as_ref(), global.key().as_ref(), oracle_event.mint.as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = global,
+        token::token_program = token_program,
+    )]
+    pub global_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_name: String)]
+pub struct ClaimPrize<'info> {
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), _name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = oracle_event.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), oracle_event.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(mut, token::mint = mint, token::authority = winner)]
+    pub winner_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct PayGlobal<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: This should match the treasury in the Config
+    #[account(mut)]
+    pub project_treasury: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], total: u64, claimants: u32)]
+pub struct PostDistribution<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"distribution".as_ref(), global.key().as_ref()],
+        bump,
+        space = MerkleDistribution::space(claimants),
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDistribution<'info> {
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    #[account(
+        mut,
+        seeds = [b"distribution".as_ref(), global.key().as_ref()],
+        bump = distribution.bump
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], total: u64, claimants: u32)]
+pub struct PostTokenDistribution<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    #[account(
+        seeds = [b"distribution".as_ref(), global.key().as_ref()],
+        bump = sol_distribution.bump
+    )]
+    pub sol_distribution: Account<'info, MerkleDistribution>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"distribution".as_ref(), global.key().as_ref(), mint.key().as_ref()],
+        bump,
+        space = MerkleDistribution::space(claimants),
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTokenDistribution<'info> {
+    pub winner: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"distribution".as_ref(), global.key().as_ref(), mint.key().as_ref()],
+        bump = distribution.bump
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), global.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub global_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint, token::authority = winner)]
+    pub winner_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetGlobalPayout<'info> {
+    pub authority: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimGlobalPrize<'info> {
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimGlobalTokenPrize<'info> {
+    pub winner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), global.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub global_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, token::mint = mint, token::authority = winner)]
+    pub winner_token: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePredictionPda<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+}
+
+#[derive(Accounts)]
+pub struct CleanPrediction<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), oracle_event.name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        close = cranker,
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), prediction.owner.as_ref()],
+        bump = prediction.bump
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+    /// CHECK: Receives the rent, checked against the prediction's owner
+    #[account(mut, address = prediction.owner)]
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CleanRefund<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"OracleEvent".as_ref(), oracle_event.name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        close = cranker,
+        seeds = [b"PodiumPrediction".as_ref(), oracle_event.key().as_ref(), prediction.owner.as_ref()],
+        bump = prediction.bump
+    )]
+    pub prediction: Account<'info, PodiumPrediction>,
+    #[account(
+        mut,
+        seeds = [b"points".as_ref(), oracle_event.season.as_ref(), prediction.owner.as_ref()],
+        bump = player_points.bump
+    )]
+    pub player_points: Account<'info, PlayerPoints>,
+    /// CHECK: Receives the refund and rent, checked against the prediction's owner
+    #[account(mut, address = prediction.owner)]
+    pub owner: AccountInfo<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(address = oracle_event.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub owner_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), oracle_event.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEventPda<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(
+        mut,
+        close = cranker,
+        seeds = [b"OracleEvent".as_ref(), oracle_event.name.as_ref()],
+        bump = oracle_event.bump
+    )]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        close = cranker,
+        seeds = [b"EventResults".as_ref(), oracle_event.name.as_ref()],
+        bump = results.bump
+    )]
+    pub results: Account<'info, EventResults>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Receives the rent, checked against the event's maker
+    #[account(mut, address = oracle_event.maker)]
+    pub maker: AccountInfo<'info>,
+    #[account(address = oracle_event.mint)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), oracle_event.key().as_ref()],
+        bump
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(mut, token::mint = mint, token::authority = maker)]
+    pub maker_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePlayerPoints<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"points".as_ref(), global.key().as_ref(), player.key().as_ref()],
+        bump = player_points.bump
+    )]
+    pub player_points: Account<'info, PlayerPoints>,
+}
+
+#[derive(Accounts)]
+pub struct CleanPlayerPoints<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    #[account(
+        mut,
+        close = cranker,
+        seeds = [b"points".as_ref(), global.key().as_ref(), player_points.pubkey.as_ref()],
+        bump = player_points.bump
+    )]
+    pub player_points: Account<'info, PlayerPoints>,
+    /// CHECK: Receives the rent, checked against the points' player
+    #[account(mut, address = player_points.pubkey)]
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLeaderboardPda<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = opened_by,
+        seeds = [b"season".as_ref(), global.name.as_ref()],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Leaderboard>,
+    /// CHECK: Receives the rent, checked against the season's opener
+    #[account(mut, address = global.opened_by)]
+    pub opened_by: AccountInfo<'info>,
+    /// CHECK: Receives what the season holds above its rent, checked against the Config
+    #[account(mut, address = config.treasury)]
+    pub project_treasury: AccountInfo<'info>,
+    /// CHECK: The season's rankings, closed if they were ever opened
+    #[account(mut, seeds = [b"rankings".as_ref(), global.key().as_ref()], bump)]
+    pub rankings: AccountInfo<'info>,
+    /// CHECK: The season's SOL distribution, closed if one was posted
+    #[account(mut, seeds = [b"distribution".as_ref(), global.key().as_ref()], bump)]
+    pub distribution: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OracleResolve<'info> {
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+    #[account(mut)]
+    pub oracle_event: Account<'info, OracleEvent>,
+    #[account(
+        mut,
+        seeds = [b"EventResults".as_ref(), oracle_event.name.as_ref()],
+        bump = results.bump
+    )]
+    pub results: Account<'info, EventResults>,
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Program-wide settings, created once by the upgrade authority.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub treasury: Pubkey,
+    pub fees: FeeRates,
+    pub pause: PauseFlags,
+    pub bump: u8,
+}
+
+/// Emergency stops, one per stage of an event, so payouts can be halted
+/// while picks are still accepted, or new events held back while the open
+/// ones play out.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct PauseFlags {
+    pub creation: bool,
+    pub predictions: bool,
+    pub resolution: bool,
+    pub processing: bool,
+    pub payouts: bool,
+}
+
+/// Allows `creator` to open events. `initialize` requires this account, so
+/// unapproved creators can't open events at all, and a stranger can't stall
+/// `pay_global` with a season event that never settles.
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorApproval {
+    pub creator: Pubkey,
+    pub approved_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct PodiumPrediction {
+    pub picks: Vec<u8>,
+    pub commitment: [u8; 32],
+    pub event: Pubkey,
+    pub timestamp: i64,
+    pub owner: Pubkey,
+    pub settled_at: i64,
+    pub points: u128,
+    pub bump: u8,
+}
+
+impl PodiumPrediction {
+    pub fn space(positions: u8) -> usize {
+        8 + 4 + positions as usize + 32 + 32 + 8 + 32 + 8 + 16 + 1
+    }
+
+    /// Set once by `process_results`, together with the points it awarded.
+    pub fn is_settled(&self) -> bool {
+        self.settled_at != 0
+    }
+}
+
+/// Layout of predictions made by the first deployment, while they were
+/// addressed by `plays`. Only read by `migrate_prediction`.
+#[derive(AnchorDeserialize)]
+pub struct LegacyPrediction {
+    pub gold: u8,
+    pub silver: u8,
+    pub bronze: u8,
+    pub event: Pubkey,
+    pub timestamp: i64,
+    pub owner: Pubkey,
+    pub bump: u8,
+}
+
+/// Layout of events created by the first deployment, which allocated a
+/// fixed `LEGACY_EVENT_SPACE` bytes for them. Only read by `migrate_event`.
+#[derive(AnchorDeserialize)]
+pub struct LegacyOracleEvent {
+    pub open_until_time: i64,
+    pub resolultion_time: i64,
+    pub solved_at: i64,
+    pub resolver: Pubkey,
+    pub fee: u64,
+    pub creator_basis_points: u16,
+    pub fee_receiver: Pubkey,
+    pub plays: u128,
+    pub settled: u128,
+    pub payed: bool,
+    pub bump: u8,
+    pub gold: u8,
+    pub silver: u8,
+    pub bronze: u8,
+    pub leaderboard: Vec<Player>,
+    pub name: String,
+}
+
+impl LegacyOracleEvent {
+    /// Current events are never `LEGACY_EVENT_SPACE` bytes long, so the
+    /// size tells the layouts apart.
+    fn load(info: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*info.owner, crate::ID);
+        let data = info.try_borrow_data()?;
+        require!(
+            data.len() == LEGACY_EVENT_SPACE && data.starts_with(&OracleEvent::DISCRIMINATOR),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        Ok(Self::deserialize(&mut &data[8..])?)
+    }
+}
+
+#[account]
+pub struct OracleEvent {
+    pub open_until_time: i64,
+    pub reveal_until: i64,
+    pub resolultion_time: i64,
+    pub solved_at: i64,
+    pub payed_at: i64,
+    /// When the event was cancelled or abandoned; starts the retention
+    /// period after which `clean_refund` may refund unclaimed entries.
+    pub voided_at: i64,
+    pub resolver: Pubkey,
+    pub backup_resolver: Pubkey,
+    pub grace_period: i64,
+    pub arbitrator: Pubkey,
+    pub dispute_period: i64,
+    pub dispute_bond: u64,
+    pub open_disputes: u16,
+    pub fee: u64,
+    pub withdraw_penalty: u16,
+    pub mint: Pubkey,
+    pub fees: FeeRates,
+    pub fee_receiver: Pubkey,
+    /// Paid the rent for the event and its results, and gets it back when
+    /// they're closed.
+    pub maker: Pubkey,
+    pub season: Pubkey,
+    pub plays: u128,
+    pub settled: u128,
+    /// Predictions closed after settlement or refund. The event can be
+    /// cleaned up once it equals `plays`.
+    pub closed: u128,
+    pub pool: u64,
+    pub payout: Vec<u16>,
+    pub payed: bool,
+    pub cancelled: bool,
+    pub abandoned: bool,
+    pub bump: u8,
+    pub positions: u8,
+    pub podium: Podium,
+    pub scoring: ScoringRules,
+    pub leaderboard: Vec<Player>,
+    pub prizes: Vec<Prize>,
+    pub name: String,
+}
+
+impl OracleEvent {
+    pub fn space(positions: u8) -> usize {
+        8 + 8 * 6
+            + 32 * 2
+            + 8
+            + 32
+            + 8
+            + 8
+            + 2
+            + 8
+            + 2
+            + 32
+            + FeeRates::INIT_SPACE
+            + 32 * 3
+            + 16 * 3
+            + 8
+            + 5
+            + Podium::space(positions)
+            + ScoringRules::space(positions)
+            + 4
+            + 2 * MAX_PAID_PLACES
+            + 4
+            + ((8 + 16 + 32) * MAX_LEADERBOARD)
+            + 4
+            + (Prize::INIT_SPACE * MAX_PAID_PLACES)
+            + 4
+            + 32
+    }
+
+    /// Last moment the primary resolver has exclusive rights to `resolve`.
+    /// Deadlines saturate, so a huge grace period means "never" instead of
+    /// wrapping into the past.
+    pub fn resolution_deadline(&self) -> i64 {
+        self.resolultion_time.saturating_add(self.grace_period)
+    }
+
+    /// Moment after which anyone may abandon the event. A configured backup
+    /// resolver gets one extra grace period before that happens.
+    pub fn abandon_deadline(&self) -> i64 {
+        if self.backup_resolver == Pubkey::default() {
+            self.resolution_deadline()
+        } else {
+            self.resolution_deadline().saturating_add(self.grace_period)
+        }
+    }
+
+    /// Results can be disputed and amended until this moment.
+    pub fn dispute_deadline(&self) -> i64 {
+        self.solved_at.saturating_add(self.dispute_period)
+    }
+
+    /// Commit-reveal events hide picks until `open_until_time` and take
+    /// reveals until `reveal_until`.
+    pub fn is_commit_reveal(&self) -> bool {
+        self.reveal_until != 0
+    }
+
+    /// Results can be processed once the event is resolved and its dispute
+    /// window has passed without open disputes.
+    pub fn ensure_processable(&self, now: i64) -> Result<()> {
+        self.ensure_active()?;
+        if self.solved_at == 0 {
+            return Err(PotreroError::EventNotResolved.into());
+        }
+        if now < self.dispute_deadline() {
+            return Err(PotreroError::DisputeWindowOpen.into());
+        }
+        require_eq!(self.open_disputes, 0, PotreroError::DisputeWindowOpen);
+        Ok(())
+    }
+
+    /// Events created with a mint take fees and pay out in that token
+    /// instead of SOL.
+    pub fn is_token(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+
+    /// Cancelled and abandoned events only allow refunds.
+    pub fn is_void(&self) -> bool {
+        self.cancelled || self.abandoned
+    }
+
+    /// A paid event can be cleaned up once every prize is claimed and the
+    /// retention period is over, a void one once every entry is refunded.
+    /// Either way no prediction may be left open.
+    pub fn ensure_cleanable(&self, now: i64) -> Result<()> {
+        require!(self.payed || self.is_void(), PotreroError::EventNotPayed);
+        require!(self.closed == self.plays, PotreroError::OpenPredictions);
+        require!(
+            self.prizes.iter().all(|prize| prize.claimed),
+            PotreroError::UnclaimedPrizes
+        );
+        require!(
+            !self.payed || now >= self.payed_at + CLEANUP_RETENTION,
+            PotreroError::RetentionPeriod
+        );
+        Ok(())
+    }
+
+    pub fn ensure_active(&self) -> Result<()> {
+        require!(!self.cancelled, PotreroError::EventCancelled);
+        require!(!self.abandoned, PotreroError::EventAbandoned);
+        Ok(())
+    }
+}
+
+#[account]
+pub struct EventResults {
+    pub resolvers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub votes: Vec<ResolverVote>,
+    pub amendments: Vec<Amendment>,
+    pub bump: u8,
+}
+
+impl EventResults {
+    pub fn space(positions: u8) -> usize {
+        let podium = Podium::space(positions);
+        8 + 4
+            + 32 * MAX_RESOLVERS
+            + 1
+            + 4
+            + (32 + podium) * MAX_RESOLVERS
+            + 4
+            + (podium * 2 + 8) * MAX_AMENDMENTS
+            + 1
+    }
+
+    /// Records a resolver's vote, replacing any earlier vote of theirs, and
+    /// returns whether `threshold` resolvers now agree on its podium.
+    /// Disagreeing votes are kept as long as some podium can still reach the
+    /// threshold with the resolvers yet to vote; a vote that rules that out
+    /// is rejected and leaves the earlier votes standing.
+    pub fn record_vote(&mut self, vote: ResolverVote) -> Result<bool> {
+        let mut votes = self.votes.clone();
+        votes.retain(|v| v.resolver != vote.resolver);
+        votes.push(vote);
+        let agreeing = |podium: &Podium| votes.iter().filter(|v| &v.podium == podium).count();
+        let threshold = self.threshold as usize;
+        if agreeing(&votes[votes.len() - 1].podium) >= threshold {
+            self.votes = votes;
+            return Ok(true);
+        }
+        let undecided = self.resolvers.len().saturating_sub(votes.len());
+        let best = votes.iter().map(|v| agreeing(&v.podium)).max().unwrap_or(0);
+        require!(
+            best + undecided >= threshold,
+            PotreroError::ConflictingVotes
+        );
+        self.votes = votes;
+        Ok(false)
+    }
+}
+
+/// Official finishing order, one entry per ranked position. Each position
+/// holds every athlete awarded it, so dead heats and double bronzes are
+/// represented by more than one entry.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Default)]
+pub struct Podium {
+    pub places: Vec<Vec<u8>>,
+}
+
+impl Podium {
+    pub fn new(mut places: Vec<Vec<u8>>, positions: u8) -> Result<Self> {
+        require!(
+            places.len() == positions as usize
+                && !places[0].is_empty()
+                && places.iter().all(|place| place.len() <= MAX_TIED),
+            PotreroError::InvalidPodium
+        );
+        for place in places.iter_mut() {
+            place.sort_unstable();
+        }
+        let mut athletes = places.concat();
+        athletes.sort_unstable();
+        require!(
+            athletes.windows(2).all(|w| w[0] != w[1]),
+            PotreroError::InvalidPodium
+        );
+        Ok(Self { places })
+    }
+
+    pub fn space(positions: u8) -> usize {
+        4 + (4 + MAX_TIED) * positions as usize
+    }
+
+    pub fn contains(&self, athlete: &u8) -> bool {
+        self.places.iter().any(|place| place.contains(athlete))
+    }
+}
+
+/// Points schedule chosen by the event creator. A pick in its exact position
+/// earns that position's `exact` points, a pick ranked elsewhere earns
+/// `on_podium`, and a fully exact prediction adds `perfect_bonus` on top.
+/// A position left empty by a tie above it can't be matched exactly, so it
+/// counts toward the bonus when filled with one of the tied athletes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ScoringRules {
+    pub exact: Vec<u16>,
+    pub on_podium: u16,
+    pub perfect_bonus: u16,
+}
+
+impl ScoringRules {
+    pub fn space(positions: u8) -> usize {
+        4 + 2 * positions as usize + 2 + 2
+    }
+
+    pub fn score(&self, podium: &Podium, picks: &[u8]) -> u128 {
+        let mut points: u128 = 0;
+        let mut perfect = true;
+        let mut tied: &[u8] = &[];
+        for ((pick, place), slot_points) in picks.iter().zip(&podium.places).zip(&self.exact) {
+            if place.contains(pick) {
+                points += *slot_points as u128;
+            } else if podium.contains(pick) {
+                points += self.on_podium as u128;
+            }
+            if place.is_empty() {
+                perfect &= tied.contains(pick);
+            } else {
+                tied = place;
+                perfect &= place.contains(pick);
+            }
+        }
+        if perfect {
+            points += self.perfect_bonus as u128;
+        }
+        points
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ResolverVote {
+    pub resolver: Pubkey,
+    pub podium: Podium,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Amendment {
+    pub before: Podium,
+    pub after: Podium,
+    pub amended_at: i64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    pub player: Pubkey,
+    pub event: Pubkey,
+    pub bond: u64,
+    pub opened_at: i64,
+    pub bump: u8,
+}
+
+/// One per player and event under the old prediction scheme. Predictions
+/// are now unique by address, so locks are only read when migrating.
+#[account]
+pub struct Lock {
     pub bump: u8,
 }
 
@@ -854,25 +2967,769 @@ pub struct PlayerPoints {
     pub events: u16,
     pub settled: u16,
     pub bump: u8,
+    /// The key of the player's entry on the season's rankings, as of the
+    /// last `rank_player`, so it can be found by binary search.
+    pub ranked: bool,
+    pub ranked_score: u64,
+    pub ranked_at: i64,
+}
+
+impl PlayerPoints {
+    /// Refunds of void events still count against the points, so they can
+    /// only close once every entry is settled.
+    fn ensure_closable(&self) -> Result<()> {
+        require_eq!(self.settled, self.events, PotreroError::OpenPredictions);
+        Ok(())
+    }
+
+    fn ranked_entry(&self) -> Option<RankEntry> {
+        self.ranked.then_some(RankEntry {
+            pubkey: self.pubkey,
+            score: self.ranked_score,
+            timestamp: self.ranked_at,
+        })
+    }
+}
+
+/// A season's ranking of up to `capacity` players, in board order. The
+/// header is followed by `capacity` entry slots, of which the first `len`
+/// are used; `grow_rankings` reallocates the account to add slots.
+#[account(zero_copy)]
+pub struct Rankings {
+    pub season: Pubkey,
+    pub len: u32,
+    pub capacity: u32,
+    pub bump: u8,
+    pub _padding: [u8; 7],
 }
 
+impl Rankings {
+    const PAGE_SPACE: usize = RANKINGS_PAGE as usize * std::mem::size_of::<RankEntry>();
+    const SPACE: usize = 8 + std::mem::size_of::<Rankings>() + Self::PAGE_SPACE;
+
+    /// Splits the account data into the header and its `capacity` slots,
+    /// which must be exactly the data after the header.
+    fn split(data: &[u8]) -> Result<(&Rankings, &[RankEntry])> {
+        let (header, slots) = data[8..].split_at(std::mem::size_of::<Rankings>());
+        let header: &Rankings =
+            bytemuck::try_from_bytes(header).map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        let slots: &[RankEntry] =
+            bytemuck::try_cast_slice(slots).map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        require_eq!(
+            slots.len(),
+            header.capacity as usize,
+            ErrorCode::AccountDidNotDeserialize
+        );
+        Ok((header, slots))
+    }
+
+    fn split_mut(data: &mut [u8]) -> Result<(&mut Rankings, &mut [RankEntry])> {
+        let (header, slots) = data[8..].split_at_mut(std::mem::size_of::<Rankings>());
+        let header: &mut Rankings = bytemuck::try_from_bytes_mut(header)
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        let slots: &mut [RankEntry] =
+            bytemuck::try_cast_slice_mut(slots).map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        require_eq!(
+            slots.len(),
+            header.capacity as usize,
+            ErrorCode::AccountDidNotDeserialize
+        );
+        Ok((header, slots))
+    }
+}
+
+#[zero_copy]
+#[derive(Debug, PartialEq)]
+pub struct RankEntry {
+    pub pubkey: Pubkey,
+    pub score: u64,
+    pub timestamp: i64,
+}
+
+/// A season's board: the events created while it was open feed its
+/// leaderboard, counters and global pool.
 #[account]
 pub struct Leaderboard {
     pub leaderboard: Vec<Player>,
     pub settled: u128,
     pub events: u128,
+    /// How many `PlayerPoints` of this season are still open.
+    pub players: u32,
+    pub pool: u64,
+    pub payed: bool,
+    pub payed_at: i64,
+    pub payout: Vec<u16>,
+    pub prizes: Vec<Prize>,
+    pub token_pools: Vec<TokenPool>,
+    pub open: bool,
+    pub opened_by: Pubkey,
+    pub bump: u8,
+    pub name: String,
 }
 
 impl Leaderboard {
-    const LEN: usize = 8 + 16 + 16 + ((8 + 16 + 32) * 10);
+    const LEN: usize = 8
+        + 4
+        + ((8 + 16 + 32) * MAX_LEADERBOARD)
+        + 16
+        + 16
+        + 4
+        + 8
+        + 1
+        + 8
+        + 4
+        + 2 * MAX_PAID_PLACES
+        + 4
+        + (Prize::INIT_SPACE * MAX_PAID_PLACES)
+        + 4
+        + (TokenPool::INIT_SPACE * MAX_TOKEN_POOLS)
+        + 1
+        + 32
+        + 1
+        + 4
+        + MAX_SEASON_NAME;
+
+    pub fn add_to_token_pool(&mut self, mint: Pubkey, amount: u64) -> Result<()> {
+        if let Some(token_pool) = self.token_pools.iter_mut().find(|pool| pool.mint == mint) {
+            token_pool.amount = token_pool
+                .amount
+                .checked_add(amount)
+                .ok_or(PotreroError::MathOverflow)?;
+            return Ok(());
+        }
+        require!(
+            self.token_pools.len() < MAX_TOKEN_POOLS,
+            PotreroError::TooManyTokenPools
+        );
+        self.token_pools.push(TokenPool {
+            mint,
+            amount,
+            claimed: 0,
+            distributed: false,
+        });
+        Ok(())
+    }
+}
+
+/// A season's pool paid by Merkle proof. `bitmap` has one bit per leaf
+/// index, set once that leaf is claimed.
+#[account]
+pub struct MerkleDistribution {
+    pub season: Pubkey,
+    pub root: [u8; 32],
+    pub total: u64,
+    pub claimed: u64,
+    pub claimants: u32,
+    pub bitmap: Vec<u8>,
+    pub bump: u8,
+}
+
+impl MerkleDistribution {
+    fn bitmap_len(claimants: u32) -> usize {
+        (claimants as usize).div_ceil(8)
+    }
+
+    fn space(claimants: u32) -> usize {
+        8 + 32 + 32 + 8 + 8 + 4 + 4 + Self::bitmap_len(claimants) + 1
+    }
+
+    /// Checks `proof` for the leaf and marks it claimed.
+    fn claim(
+        &mut self,
+        index: u32,
+        player: &Pubkey,
+        amount: u64,
+        proof: &[[u8; 32]],
+    ) -> Result<()> {
+        require!(index < self.claimants, PotreroError::InvalidProof);
+        require!(
+            verify_proof(self.root, merkle_leaf(index, player, amount), proof),
+            PotreroError::InvalidProof
+        );
+        let (byte, bit) = (index as usize / 8, 1 << (index % 8));
+        require!(self.bitmap[byte] & bit == 0, PotreroError::NoPrize);
+        self.bitmap[byte] |= bit;
+        self.claimed = self
+            .claimed
+            .checked_add(amount)
+            .filter(|claimed| *claimed <= self.total)
+            .ok_or(PotreroError::InvalidDistribution)?;
+        Ok(())
+    }
+}
+
+/// Global cut collected from events paid in an SPL token. Winners claim
+/// their place's share of `amount`; `claimed` has one bit per place.
+/// `distributed` pools are paid through `post_token_distribution` instead.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct TokenPool {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub claimed: u16,
+    pub distributed: bool,
 }
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug, PartialEq)]
 pub struct Player {
     pub pubkey: Pubkey,
     pub score: u128,
     pub timestamp: i64,
 }
 
+/// A winner's share of a settled pool, collected through a claim instruction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct Prize {
+    pub winner: Pubkey,
+    pub amount: u64,
+    pub claimed: bool,
+}
+
+/// The hash a player commits to in commit-reveal events. Binding the player
+/// stops others from copying a commitment and replaying its reveal.
+fn commitment(picks: &[u8], salt: &[u8; 32], player: &Pubkey) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hashv(&[picks, salt, player.as_ref()]).to_bytes()
+}
+
+/// Picks name one distinct competitor for each of the event's positions.
+fn validate_picks(picks: &[u8], positions: u8) -> Result<()> {
+    require!(
+        picks.len() == positions as usize
+            && picks
+                .iter()
+                .enumerate()
+                .all(|(i, pick)| !picks[..i].contains(pick)),
+        PotreroError::InvalidPrediction
+    );
+    Ok(())
+}
+
+/// A payout table pays between one and ten places, each a positive number of
+/// basis points, adding up to the whole pool.
+fn validate_payout(payout: &[u16]) -> Result<()> {
+    require!(
+        !payout.is_empty()
+            && payout.len() <= MAX_PAID_PLACES
+            && payout.iter().all(|bps| *bps > 0)
+            && payout.iter().map(|bps| *bps as u32).sum::<u32>() == 10_000,
+        PotreroError::InvalidPayout
+    );
+    Ok(())
+}
+
+/// Cuts taken from an event pool before prizes, in basis points of the pool.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct FeeRates {
+    pub creator_basis_points: u16,
+    pub global_basis_points: u16,
+    pub treasury_basis_points: u16,
+}
+
+impl FeeRates {
+    pub fn validate(&self) -> Result<()> {
+        let total = self.creator_basis_points as u32
+            + self.global_basis_points as u32
+            + self.treasury_basis_points as u32;
+        require!(total <= 10_000, PotreroError::InvalidFees);
+        Ok(())
+    }
+}
+
+/// How a settled event pool is paid out. The amounts always add up to the
+/// pool they were computed from.
+#[derive(Debug)]
+pub struct Distribution {
+    pub creator: u64,
+    pub global: u64,
+    pub treasury: u64,
+    pub prizes: Vec<u64>,
+}
+
+/// The cranker's share of an account it closes. Only the rent counts, so
+/// any lamports left over from the pool go back in full.
+fn cleanup_reward(lamports: u64, rent: u64) -> u64 {
+    basis_points_of(lamports.min(rent), CLEANUP_REWARD_BASIS_POINTS)
+}
+
+/// Moves all but the cranker's reward out of an account closed by a
+/// cleanup crank to whoever funded it; `close` sends the reward. Cranks
+/// keep `CLEANUP_REWARD_BASIS_POINTS` of the rent so closing other people's
+/// accounts pays for itself, and the rest goes back to the funder.
+fn refund_cleanup<'info>(account: &AccountInfo<'info>, funder: &AccountInfo<'info>) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(account.data_len());
+    let refund = account.lamports() - cleanup_reward(account.lamports(), rent);
+    account.sub_lamports(refund)?;
+    funder.add_lamports(refund)?;
+    Ok(())
+}
+
+/// Closes a fully claimed Merkle distribution.
+fn close_distribution<'info>(
+    account: &AccountInfo<'info>,
+    receiver: &AccountInfo<'info>,
+) -> Result<()> {
+    require_keys_eq!(*account.owner, crate::ID);
+    let distribution = MerkleDistribution::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    require!(
+        distribution.claimed == distribution.total,
+        PotreroError::UnclaimedPrizes
+    );
+    close_program_account(account, receiver)
+}
+
+/// Closes an account outside of Anchor's `close` constraint, for accounts
+/// that may not exist or can't be deserialized.
+fn close_program_account<'info>(
+    account: &AccountInfo<'info>,
+    receiver: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = account.lamports();
+    account.sub_lamports(lamports)?;
+    receiver.add_lamports(lamports)?;
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+fn basis_points_of(amount: u64, basis_points: u16) -> u64 {
+    // Can't overflow: the product fits in u128 and the result is <= amount.
+    (amount as u128 * basis_points as u128 / 10_000) as u64
+}
+
+/// Takes the fee cuts out of `pool` and splits what is left over the first
+/// `winners` places of `payout`. When nobody is ranked, the prize money goes
+/// to the global pool instead.
+fn distribute(pool: u64, fees: &FeeRates, payout: &[u16], winners: usize) -> Result<Distribution> {
+    let creator = basis_points_of(pool, fees.creator_basis_points);
+    let mut global = basis_points_of(pool, fees.global_basis_points);
+    let treasury = basis_points_of(pool, fees.treasury_basis_points);
+    let remainder = pool
+        .checked_sub(creator)
+        .and_then(|rest| rest.checked_sub(global))
+        .and_then(|rest| rest.checked_sub(treasury))
+        .ok_or(PotreroError::MathOverflow)?;
+    let prizes = split_pool(remainder, payout, winners)?;
+    if prizes.is_empty() {
+        global = global
+            .checked_add(remainder)
+            .ok_or(PotreroError::MathOverflow)?;
+    }
+    Ok(Distribution {
+        creator,
+        global,
+        treasury,
+        prizes,
+    })
+}
+
+/// Splits `pool` over the first `winners` places of `payout`. When fewer
+/// players are ranked than places are paid, the shares of the empty places
+/// are spread over the others. Rounding dust goes to first place.
+fn split_pool(pool: u64, payout: &[u16], winners: usize) -> Result<Vec<u64>> {
+    let shares = &payout[..winners.min(payout.len())];
+    let total: u128 = shares.iter().map(|bps| *bps as u128).sum();
+    let mut prizes: Vec<u64> = shares
+        .iter()
+        .map(|bps| (pool as u128 * *bps as u128 / total) as u64)
+        .collect();
+    let paid = prizes
+        .iter()
+        .try_fold(0u64, |paid, prize| paid.checked_add(*prize))
+        .ok_or(PotreroError::MathOverflow)?;
+    if let Some(first) = prizes.first_mut() {
+        *first = pool
+            .checked_sub(paid)
+            .and_then(|dust| first.checked_add(dust))
+            .ok_or(PotreroError::MathOverflow)?;
+    }
+    Ok(prizes)
+}
+
+fn prizes_for(leaderboard: &[Player], amounts: &[u64]) -> Vec<Prize> {
+    leaderboard
+        .iter()
+        .zip(amounts)
+        .map(|(player, amount)| Prize {
+            winner: player.pubkey,
+            amount: *amount,
+            claimed: false,
+        })
+        .collect()
+}
+
+/// Scores one prediction and updates the event and global leaderboards.
+/// Callers check that the event can be processed and the prediction isn't
+/// settled yet.
+fn settle(
+    event: &mut OracleEvent,
+    global: &mut Leaderboard,
+    prediction: &mut PodiumPrediction,
+    player_points: &mut PlayerPoints,
+    now: i64,
+) {
+    // Commitments that were never revealed have no picks and score zero.
+    let revealed = !prediction.picks.is_empty();
+    let points = if revealed {
+        event.scoring.score(&event.podium, &prediction.picks)
+    } else {
+        0
+    };
+    prediction.settled_at = now;
+    prediction.points = points;
+    player_points.score += points;
+    player_points.settled += 1;
+    event.settled += 1;
+
+    if !revealed {
+        return;
+    }
+    let owner = prediction.owner;
+    rank(
+        &mut event.leaderboard,
+        Player {
+            pubkey: owner,
+            score: points,
+            timestamp: prediction.timestamp,
+        },
+        MAX_LEADERBOARD,
+    );
+    rank(
+        &mut global.leaderboard,
+        Player {
+            pubkey: owner,
+            score: player_points.score,
+            timestamp: player_points.timestamp,
+        },
+        MAX_LEADERBOARD,
+    );
+}
+
+/// Board order: higher score first, ties to the earlier timestamp and then
+/// to the lower key, so every board has exactly one valid order.
+fn ranks_before(a: &Player, b: &Player) -> Ordering {
+    b.score
+        .cmp(&a.score)
+        .then(a.timestamp.cmp(&b.timestamp))
+        .then(a.pubkey.cmp(&b.pubkey))
+}
+
+/// Puts `entry` in its place on a board of at most `capacity` players,
+/// replacing the player's previous entry. An update that doesn't raise the
+/// player's score leaves the board alone, so entries only ever move up and
+/// the board stays the true top `capacity` while scores never drop.
+fn rank(board: &mut Vec<Player>, entry: Player, capacity: usize) {
+    if let Some(index) = board.iter().position(|p| p.pubkey == entry.pubkey) {
+        if entry.score <= board[index].score {
+            return;
+        }
+        board.remove(index);
+    }
+    let index = board.partition_point(|p| ranks_before(p, &entry) == Ordering::Less);
+    if index < capacity {
+        board.insert(index, entry);
+        board.truncate(capacity);
+    }
+}
+
+/// `ranks_before` for rankings entries.
+fn ranking_order(a: &RankEntry, b: &RankEntry) -> Ordering {
+    b.score
+        .cmp(&a.score)
+        .then(a.timestamp.cmp(&b.timestamp))
+        .then(a.pubkey.cmp(&b.pubkey))
+}
+
+/// `rank` for the first `len` of `slots`, where `previous` is the player's
+/// current entry if they were ranked. Returns the new length and the
+/// player's entry on the board, if they're on it. Both lookups are binary
+/// searches and the move is one memmove, so an insert stays cheap however
+/// many slots the account has grown to. A `previous` that isn't found was
+/// pushed off the end and is ranked again as a new player.
+fn rank_in(
+    slots: &mut [RankEntry],
+    mut len: usize,
+    previous: Option<&RankEntry>,
+    entry: RankEntry,
+) -> (usize, Option<RankEntry>) {
+    if let Some(previous) = previous {
+        if let Ok(index) = slots[..len].binary_search_by(|slot| ranking_order(slot, previous)) {
+            if entry.score <= previous.score {
+                return (len, Some(*previous));
+            }
+            slots.copy_within(index + 1..len, index);
+            len -= 1;
+        }
+    }
+    let index = slots[..len].partition_point(|slot| ranking_order(slot, &entry) == Ordering::Less);
+    if index >= slots.len() {
+        return (len, None);
+    }
+    if len == slots.len() {
+        len -= 1;
+    }
+    slots.copy_within(index..len, index + 1);
+    slots[index] = entry;
+    (len + 1, Some(entry))
+}
+
+/// Shared by plain and committed predictions: takes the entry fee and
+/// records the prediction. Plain picks are validated by the caller.
+fn enter_prediction(
+    ctx: Context<MakePrediction>,
+    picks: Vec<u8>,
+    commitment: [u8; 32],
+) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.fee_receiver.key(),
+        ctx.accounts.oracle_event.fee_receiver
+    );
+    require!(
+        !ctx.accounts.config.pause.predictions,
+        PotreroError::PredictionsPaused
+    );
+    ctx.accounts.oracle_event.ensure_active()?;
+    let clock = Clock::get()?;
+    if clock.unix_timestamp > ctx.accounts.oracle_event.open_until_time
+        || ctx.accounts.oracle_event.solved_at != 0
+    {
+        return Err(PotreroError::EventClose.into());
+    }
+    ctx.accounts.prediction.set_inner(PodiumPrediction {
+        picks,
+        commitment,
+        event: *ctx.accounts.oracle_event.to_account_info().key,
+        timestamp: clock.unix_timestamp,
+        owner: ctx.accounts.player.key(),
+        settled_at: 0,
+        points: 0,
+        bump: ctx.bumps.prediction,
+    });
+    ctx.accounts.oracle_event.plays += 1;
+    ctx.accounts.oracle_event.pool += ctx.accounts.oracle_event.fee;
+    if ctx.accounts.player_points.pubkey == Pubkey::default() {
+        ctx.accounts.global.players += 1;
+    }
+    ctx.accounts.player_points.set_inner(PlayerPoints {
+        pubkey: ctx.accounts.player.key(),
+        score: ctx.accounts.player_points.score,
+        timestamp: clock.unix_timestamp,
+        events: ctx.accounts.player_points.events + 1,
+        settled: ctx.accounts.player_points.settled,
+        bump: ctx.bumps.player_points,
+        ranked: ctx.accounts.player_points.ranked,
+        ranked_score: ctx.accounts.player_points.ranked_score,
+        ranked_at: ctx.accounts.player_points.ranked_at,
+    });
+    if ctx.accounts.oracle_event.is_token() {
+        return transfer_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.player_token,
+            &ctx.accounts.vault,
+            ctx.accounts.player.to_account_info(),
+            &[],
+            ctx.accounts.oracle_event.fee,
+        );
+    }
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.player.to_account_info().clone(),
+            to: ctx.accounts.oracle_event.to_account_info().clone(),
+        },
+    );
+    system_program::transfer(cpi_context, ctx.accounts.oracle_event.fee)?;
+    Ok(())
+}
+
+/// Moves `amount` of an event's token between two token accounts. The
+/// accounts are optional on instructions shared with SOL events, so a
+/// missing one is reported instead of unwrapped.
+fn transfer_tokens<'info>(
+    token_program: &Option<Interface<'info, TokenInterface>>,
+    mint: &Option<Box<InterfaceAccount<'info, Mint>>>,
+    from: &Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    to: &Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let (Some(token_program), Some(mint), Some(from), Some(to)) = (token_program, mint, from, to)
+    else {
+        return Err(PotreroError::MissingTokenAccount.into());
+    };
+    if amount == 0 {
+        return Ok(());
+    }
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority,
+        },
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_context, amount, mint.decimals)
+}
+
+/// Hands a void event's entry fee back to the owner of a prediction.
+fn refund_entry<'info>(
+    event: &mut Account<'info, OracleEvent>,
+    player_points: &mut Account<'info, PlayerPoints>,
+    owner: &AccountInfo<'info>,
+    token_program: &Option<Interface<'info, TokenInterface>>,
+    mint: &Option<Box<InterfaceAccount<'info, Mint>>>,
+    vault: &Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    owner_token: &Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+) -> Result<()> {
+    player_points.events -= 1;
+    event.closed += 1;
+    let fee = event.fee;
+    event.pool -= fee;
+    if event.is_token() {
+        let bump = [event.bump];
+        let seeds = [b"OracleEvent".as_ref(), event.name.as_bytes(), &bump];
+        return transfer_tokens(
+            token_program,
+            mint,
+            vault,
+            owner_token,
+            event.to_account_info(),
+            &[&seeds],
+            fee,
+        );
+    }
+    event.sub_lamports(fee)?;
+    owner.add_lamports(fee)?;
+    Ok(())
+}
+
+/// Only lets in Token-2022 mints whose extensions leave transfers into and
+/// out of the vault alone. Others could withhold fees, freeze or seize the
+/// vault's tokens, run a hook on every transfer or close the mint under it.
+fn ensure_supported_mint(mint: &AccountInfo) -> Result<()> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<MintState>::unpack(&data)?;
+    require!(
+        mint.get_extension_types()?
+            .iter()
+            .all(|extension| SUPPORTED_MINT_EXTENSIONS.contains(extension)),
+        PotreroError::UnsupportedMint
+    );
+    Ok(())
+}
+
+/// Marks the first unclaimed prize of `winner` as claimed and returns it.
+fn claim(prizes: &mut [Prize], winner: Pubkey) -> Result<u64> {
+    let prize = prizes
+        .iter_mut()
+        .find(|prize| prize.winner == winner && !prize.claimed)
+        .ok_or(PotreroError::NoPrize)?;
+    prize.claimed = true;
+    Ok(prize.amount)
+}
+
+/// Leaves and inner nodes are hashed with different prefixes so a node
+/// can't pass for a leaf.
+fn merkle_leaf(index: u32, player: &Pubkey, amount: u64) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hashv(&[
+        &[0],
+        &index.to_le_bytes(),
+        player.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Pairs are sorted before hashing, so proofs don't need left/right flags.
+fn merkle_parent(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    anchor_lang::solana_program::hash::hashv(&[&[1], left, right]).to_bytes()
+}
+
+fn verify_proof(root: [u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| merkle_parent(&node, sibling))
+        == root
+}
+
+/// Splits a season's `pool` over its players in proportion to their
+/// scores, best first, for `MerkleTree`. Amounts round down, so they add up
+/// to at most `pool`, and players without points are left out.
+#[cfg(not(target_os = "solana"))]
+pub fn season_awards(players: &[PlayerPoints], pool: u64) -> Vec<(Pubkey, u64)> {
+    let mut players: Vec<Player> = players
+        .iter()
+        .filter(|points| points.score > 0)
+        .map(|points| Player {
+            pubkey: points.pubkey,
+            score: points.score,
+            timestamp: points.timestamp,
+        })
+        .collect();
+    players.sort_by(ranks_before);
+    let total: u128 = players.iter().map(|player| player.score).sum();
+    players
+        .iter()
+        .map(|player| {
+            let amount = (pool as u128 * player.score / total) as u64;
+            (player.pubkey, amount)
+        })
+        .collect()
+}
+
+/// Builds the root posted with `post_distribution` and each player's
+/// proof for `claim_distribution`. Leaf `i` is `awards[i]`; a node left
+/// without a sibling moves up a level unchanged.
+#[cfg(not(target_os = "solana"))]
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl MerkleTree {
+    pub fn new(awards: &[(Pubkey, u64)]) -> Self {
+        let mut levels = vec![awards
+            .iter()
+            .enumerate()
+            .map(|(index, (player, amount))| merkle_leaf(index as u32, player, *amount))
+            .collect::<Vec<_>>()];
+        while levels[levels.len() - 1].len() > 1 {
+            let level = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => merkle_parent(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(level);
+        }
+        MerkleTree { levels }
+    }
+
+    /// The empty tree's root is all zeroes, which no proof can reach.
+    pub fn root(&self) -> [u8; 32] {
+        self.levels[self.levels.len() - 1]
+            .first()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        let mut index = index;
+        let mut proof = vec![];
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
 #[error_code]
 pub enum PotreroError {
     #[msg("The Event is already closed.")]
@@ -883,4 +3740,579 @@ pub enum PotreroError {
     EventNotResolved,
     #[msg("The Event is not payed.")]
     EventNotPayed,
+    #[msg("The Event was cancelled.")]
+    EventCancelled,
+    #[msg("The Event is not cancelled.")]
+    EventNotCancelled,
+    #[msg("The Event was abandoned.")]
+    EventAbandoned,
+    #[msg("The resolver grace period has not passed.")]
+    ResolverGracePeriod,
+    #[msg("The grace period can't be negative.")]
+    InvalidGracePeriod,
+    #[msg("The resolver set or threshold is invalid.")]
+    InvalidQuorum,
+    #[msg("The votes can no longer reach the quorum on one podium.")]
+    ConflictingVotes,
+    #[msg("A dispute period needs a non-negative length, an arbitrator and the minimum bond.")]
+    InvalidDisputeConfig,
+    #[msg("The dispute window is still open.")]
+    DisputeWindowOpen,
+    #[msg("The dispute window is closed.")]
+    DisputeWindowClosed,
+    #[msg("The results can't be amended again.")]
+    TooManyAmendments,
+    #[msg("The results need a winner, one entry per position and no athlete twice.")]
+    InvalidPodium,
+    #[msg("A prediction needs one distinct athlete per position.")]
+    InvalidPrediction,
+    #[msg("The number of ranked positions is out of range.")]
+    InvalidPositions,
+    #[msg("The scoring schedule needs points for every position.")]
+    InvalidScoring,
+    #[msg("There is no prize left to claim.")]
+    NoPrize,
+    #[msg("The payout table must pay 1 to 10 places and sum to 10,000 basis points.")]
+    InvalidPayout,
+    #[msg("The fee cuts can't exceed 10,000 basis points.")]
+    InvalidFees,
+    #[msg("The Event doesn't take this kind of prediction.")]
+    WrongPredictionMode,
+    #[msg("The reveal window must end after the Event closes and before it resolves.")]
+    InvalidRevealWindow,
+    #[msg("The reveal window is not open.")]
+    RevealWindowClosed,
+    #[msg("The revealed picks don't match the commitment.")]
+    InvalidReveal,
+    #[msg("The Prediction has already been processed.")]
+    PredictionSettled,
+    #[msg("The Season is closed.")]
+    SeasonClosed,
+    #[msg("The Season is still open.")]
+    SeasonOpen,
+    #[msg("Season names are 1 to 32 bytes long.")]
+    InvalidSeasonName,
+    #[msg("The Prediction hasn't been processed yet.")]
+    PredictionNotSettled,
+    #[msg("Batches take writable prediction and player points pairs.")]
+    InvalidBatch,
+    #[msg("Arithmetic overflow.")]
+    MathOverflow,
+    #[msg("Only the program upgrade authority can do this.")]
+    Unauthorized,
+    #[msg("Predictions are paused.")]
+    PredictionsPaused,
+    #[msg("Resolution is paused.")]
+    ResolutionPaused,
+    #[msg("Result processing is paused.")]
+    ProcessingPaused,
+    #[msg("Payouts are paused.")]
+    PayoutsPaused,
+    #[msg("A token account required for this Event's mint is missing.")]
+    MissingTokenAccount,
+    #[msg("The global leaderboard can't hold another token pool.")]
+    TooManyTokenPools,
+    #[msg("The Merkle proof doesn't match the posted root.")]
+    InvalidProof,
+    #[msg("The distribution pays out more than the season's pool.")]
+    InvalidDistribution,
+    #[msg("The Event still has open predictions.")]
+    OpenPredictions,
+    #[msg("The Event still has unclaimed prizes.")]
+    UnclaimedPrizes,
+    #[msg("The retention period hasn't passed yet.")]
+    RetentionPeriod,
+    #[msg("The mint has extensions that aren't supported.")]
+    UnsupportedMint,
+    #[msg("Event creation is paused.")]
+    CreationPaused,
+    #[msg("Players still hold points in this season.")]
+    OpenPlayerPoints,
+    #[msg("Legacy events that were processed or paid can't be migrated.")]
+    LegacyEventProcessed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use std::collections::BTreeMap;
+
+    const POOLS: [u64; 9] = [
+        0,
+        1,
+        7,
+        99,
+        10_000,
+        123_456_789,
+        1_000_000_007,
+        u64::MAX / 3,
+        u64::MAX,
+    ];
+
+    fn payouts() -> Vec<Vec<u16>> {
+        vec![
+            DEFAULT_PAYOUT.to_vec(),
+            vec![10_000],
+            vec![3_333, 3_333, 3_334],
+            vec![1; 9].into_iter().chain([9_991]).collect(),
+        ]
+    }
+
+    fn fee_rates() -> Vec<FeeRates> {
+        [
+            (0, 0, 0),
+            (250, 100, 50),
+            (9_999, 0, 1),
+            (3_333, 3_333, 3_334),
+            (1, 1, 1),
+        ]
+        .into_iter()
+        .map(|(creator, global, treasury)| FeeRates {
+            creator_basis_points: creator,
+            global_basis_points: global,
+            treasury_basis_points: treasury,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn distribute_pays_out_the_whole_pool() {
+        for pool in POOLS {
+            for fees in fee_rates() {
+                for payout in payouts() {
+                    for winners in 0..=MAX_PAID_PLACES {
+                        let d = distribute(pool, &fees, &payout, winners).unwrap();
+                        let prizes: u128 = d.prizes.iter().map(|p| *p as u128).sum();
+                        let total =
+                            d.creator as u128 + d.global as u128 + d.treasury as u128 + prizes;
+                        assert_eq!(total, pool as u128, "{d:?}");
+                        assert_eq!(d.prizes.len(), winners.min(payout.len()));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn split_pool_follows_the_payout_table() {
+        let prizes = split_pool(1_000_000, &DEFAULT_PAYOUT, MAX_PAID_PLACES).unwrap();
+        assert_eq!(
+            prizes,
+            vec![350_000, 250_000, 150_000, 80_000, 50_000, 40_000, 30_000, 20_000, 20_000, 10_000]
+        );
+    }
+
+    #[test]
+    fn split_pool_spreads_empty_places_and_dust() {
+        // 3500 + 2500 + 1500 basis points shared by three winners.
+        let prizes = split_pool(1_000, &DEFAULT_PAYOUT, 3).unwrap();
+        assert_eq!(prizes, vec![467, 333, 200]);
+        assert!(split_pool(1_000, &DEFAULT_PAYOUT, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn distribute_sends_unranked_prize_money_to_the_global_pool() {
+        let fees = FeeRates {
+            creator_basis_points: 500,
+            global_basis_points: 100,
+            treasury_basis_points: 0,
+        };
+        let d = distribute(10_000, &fees, &DEFAULT_PAYOUT, 0).unwrap();
+        assert_eq!(d.creator, 500);
+        assert_eq!(d.global, 9_500);
+        assert!(d.prizes.is_empty());
+    }
+
+    #[test]
+    fn invalid_fee_rates_and_payouts_are_rejected() {
+        let fees = FeeRates {
+            creator_basis_points: 5_000,
+            global_basis_points: 5_000,
+            treasury_basis_points: 1,
+        };
+        assert!(fees.validate().is_err());
+        assert!(validate_payout(&[5_000, 4_999]).is_err());
+        assert!(validate_payout(&[10_000, 0]).is_err());
+        assert!(validate_payout(&DEFAULT_PAYOUT).is_ok());
+    }
+
+    #[test]
+    fn commitments_bind_picks_salt_and_player() {
+        let player = Pubkey::new_unique();
+        let salt = [7; 32];
+        let committed = commitment(&[1, 2, 3], &salt, &player);
+        assert_eq!(committed, commitment(&[1, 2, 3], &salt, &player));
+        assert_ne!(committed, commitment(&[2, 1, 3], &salt, &player));
+        assert_ne!(committed, commitment(&[1, 2, 3], &[8; 32], &player));
+        assert_ne!(
+            committed,
+            commitment(&[1, 2, 3], &salt, &Pubkey::new_unique())
+        );
+    }
+
+    fn player(id: u8, score: u128, timestamp: i64) -> Player {
+        Player {
+            pubkey: Pubkey::new_from_array([id; 32]),
+            score,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn rank_breaks_ties_by_timestamp_then_key() {
+        let mut board = vec![];
+        rank(&mut board, player(3, 5, 20), 3);
+        rank(&mut board, player(2, 5, 10), 3);
+        rank(&mut board, player(1, 5, 20), 3);
+        rank(&mut board, player(4, 9, 30), 3);
+        assert_eq!(
+            board,
+            vec![player(4, 9, 30), player(2, 5, 10), player(1, 5, 20)]
+        );
+    }
+
+    #[test]
+    fn rank_moves_players_up_and_ignores_stale_updates() {
+        let mut board = vec![player(1, 10, 0), player(2, 8, 0)];
+        rank(&mut board, player(2, 12, 5), 2);
+        assert_eq!(board, vec![player(2, 12, 5), player(1, 10, 0)]);
+        rank(&mut board, player(1, 10, 9), 2);
+        assert_eq!(board, vec![player(2, 12, 5), player(1, 10, 0)]);
+    }
+
+    proptest! {
+        #[test]
+        fn rank_keeps_the_true_top_k(
+            capacity in 1..=MAX_LEADERBOARD,
+            updates in vec((0..24u8, 0..50u128, 0..10i64), 0..200),
+        ) {
+            let mut board = vec![];
+            let mut everyone: BTreeMap<u8, Player> = BTreeMap::new();
+            // Players' timestamps only move forward, as on chain.
+            let mut timestamp = 0;
+            for (id, gained, elapsed) in updates {
+                timestamp += elapsed;
+                let score = everyone.get(&id).map_or(0, |p| p.score) + gained;
+                let entry = player(id, score, timestamp);
+                if everyone.get(&id).is_none_or(|p| score > p.score) {
+                    everyone.insert(id, entry.clone());
+                }
+                rank(&mut board, entry, capacity);
+
+                let mut expected: Vec<Player> = everyone.values().cloned().collect();
+                expected.sort_by(ranks_before);
+                expected.truncate(capacity);
+                prop_assert_eq!(&board, &expected);
+            }
+        }
+
+        #[test]
+        fn rank_in_matches_rank(
+            capacity in 1..40usize,
+            updates in vec((0..60u8, 0..50u64, 0..1_000i64), 0..300),
+        ) {
+            let mut slots = vec![entry(0, 0, 0); capacity];
+            let mut len = 0;
+            let mut board = vec![];
+            let mut ranked: BTreeMap<u8, RankEntry> = BTreeMap::new();
+            let mut scores: BTreeMap<u8, u64> = BTreeMap::new();
+            for (id, gained, timestamp) in updates {
+                let score = scores.get(&id).copied().unwrap_or(0) + gained;
+                scores.insert(id, score);
+                let (new_len, placed) =
+                    rank_in(&mut slots, len, ranked.get(&id), entry(id, score, timestamp));
+                len = new_len;
+                match placed {
+                    Some(placed) => ranked.insert(id, placed),
+                    None => ranked.remove(&id),
+                };
+                rank(&mut board, player(id, score.into(), timestamp), capacity);
+
+                let ranking: Vec<Player> = slots[..len].iter().map(|e| {
+                    player(e.pubkey.to_bytes()[0], e.score.into(), e.timestamp)
+                }).collect();
+                prop_assert_eq!(&ranking, &board);
+                // What the player's points remember is what `player_rank` finds.
+                for (id, remembered) in &ranked {
+                    let found = slots[..len]
+                        .binary_search_by(|slot| ranking_order(slot, remembered))
+                        .ok();
+                    let expected = board.iter().position(|p| p.pubkey.to_bytes()[0] == *id);
+                    prop_assert_eq!(found, expected);
+                }
+            }
+        }
+    }
+
+    fn quorum(resolvers: u8, threshold: u8, votes: &[(u8, u8)]) -> EventResults {
+        let mut results = EventResults {
+            resolvers: (1..=resolvers)
+                .map(|id| Pubkey::new_from_array([id; 32]))
+                .collect(),
+            threshold,
+            votes: vec![],
+            amendments: vec![],
+            bump: 0,
+        };
+        for (id, winner) in votes {
+            results.record_vote(vote(*id, *winner)).unwrap();
+        }
+        results
+    }
+
+    fn vote(id: u8, winner: u8) -> ResolverVote {
+        ResolverVote {
+            resolver: Pubkey::new_from_array([id; 32]),
+            podium: Podium {
+                places: vec![vec![winner]],
+            },
+        }
+    }
+
+    #[test]
+    fn a_quorum_forms_around_an_early_dissent() {
+        let mut results = quorum(3, 2, &[]);
+        assert!(!results.record_vote(vote(1, 9)).unwrap());
+        assert!(!results.record_vote(vote(2, 7)).unwrap());
+        assert!(results.record_vote(vote(3, 7)).unwrap());
+        assert_eq!(results.votes.len(), 3);
+
+        // A resolver changing their vote only counts once.
+        let mut results = quorum(3, 2, &[(1, 7)]);
+        assert!(!results.record_vote(vote(1, 8)).unwrap());
+        assert!(results.record_vote(vote(2, 8)).unwrap());
+    }
+
+    #[test]
+    fn votes_conflict_only_once_the_quorum_is_out_of_reach() {
+        let mut results = quorum(3, 2, &[(1, 7), (2, 8)]);
+        assert!(results.record_vote(vote(3, 9)).is_err());
+        // The rejected vote leaves the others in place.
+        assert_eq!(results.votes.len(), 2);
+        assert!(results.record_vote(vote(3, 8)).unwrap());
+
+        let mut results = quorum(3, 3, &[(1, 7)]);
+        assert!(results.record_vote(vote(2, 8)).is_err());
+        assert_eq!(results.votes.len(), 1);
+    }
+
+    #[test]
+    fn tied_athletes_fill_the_positions_their_tie_empties() {
+        let rules = ScoringRules {
+            exact: vec![3, 2, 1],
+            on_podium: 0,
+            perfect_bonus: 10,
+        };
+        let podium = Podium::new(vec![vec![1, 2], vec![], vec![3]], 3).unwrap();
+        assert_eq!(rules.score(&podium, &[1, 2, 3]), 3 + 1 + 10);
+        assert_eq!(rules.score(&podium, &[2, 1, 3]), 3 + 1 + 10);
+        assert_eq!(rules.score(&podium, &[1, 3, 2]), 3);
+        let podium = Podium::new(vec![vec![1], vec![2], vec![3, 4]], 3).unwrap();
+        assert_eq!(rules.score(&podium, &[1, 2, 4]), 3 + 2 + 1 + 10);
+        assert_eq!(rules.score(&podium, &[1, 3, 4]), 3 + 1);
+    }
+
+    #[test]
+    fn podium_athletes_in_the_wrong_slot_earn_on_podium_points() {
+        let rules = ScoringRules {
+            exact: vec![5, 3, 1],
+            on_podium: 2,
+            perfect_bonus: 0,
+        };
+        let podium = Podium::new(vec![vec![1], vec![2], vec![3]], 3).unwrap();
+        assert_eq!(rules.score(&podium, &[1, 2, 3]), 5 + 3 + 1);
+        assert_eq!(rules.score(&podium, &[2, 1, 3]), 2 + 2 + 1);
+        assert_eq!(rules.score(&podium, &[3, 1, 2]), 2 + 2 + 2);
+        // Athletes off the podium earn nothing anywhere.
+        assert_eq!(rules.score(&podium, &[1, 2, 4]), 5 + 3);
+        assert_eq!(rules.score(&podium, &[4, 5, 6]), 0);
+        // Nobody finishes in a tie-emptied slot, so a podium athlete there
+        // only earns on_podium.
+        let podium = Podium::new(vec![vec![1, 2], vec![], vec![3]], 3).unwrap();
+        assert_eq!(rules.score(&podium, &[1, 3, 2]), 5 + 2 + 2);
+        assert_eq!(rules.score(&podium, &[3, 1, 2]), 2 + 2 + 2);
+    }
+
+    #[test]
+    fn current_events_never_have_the_legacy_size() {
+        for positions in 1..=MAX_POSITIONS {
+            assert_ne!(OracleEvent::space(positions), LEGACY_EVENT_SPACE);
+        }
+    }
+
+    #[test]
+    fn cleanup_rewards_only_share_the_rent() {
+        assert_eq!(cleanup_reward(2_000_000, 2_000_000), 200_000);
+        assert_eq!(cleanup_reward(9_000_000, 2_000_000), 200_000);
+        assert_eq!(cleanup_reward(1_000, 2_000_000), 100);
+        assert_eq!(cleanup_reward(0, 2_000_000), 0);
+    }
+
+    fn points(id: u8, score: u128) -> PlayerPoints {
+        PlayerPoints {
+            pubkey: Pubkey::new_from_array([id; 32]),
+            score,
+            timestamp: 0,
+            events: 1,
+            settled: 1,
+            bump: 0,
+            ranked: false,
+            ranked_score: 0,
+            ranked_at: 0,
+        }
+    }
+
+    #[test]
+    fn season_awards_are_pro_rata_and_skip_empty_scores() {
+        let awards = season_awards(&[points(1, 1), points(2, 0), points(3, 2)], 100);
+        assert_eq!(
+            awards,
+            vec![
+                (Pubkey::new_from_array([3; 32]), 66),
+                (Pubkey::new_from_array([1; 32]), 33),
+            ]
+        );
+        assert!(season_awards(&[points(1, 0)], 100).is_empty());
+    }
+
+    #[test]
+    fn merkle_proofs_verify_only_their_own_leaf() {
+        for size in 1..=9u8 {
+            let awards: Vec<(Pubkey, u64)> = (0..size)
+                .map(|id| (Pubkey::new_from_array([id; 32]), id as u64 * 10))
+                .collect();
+            let tree = MerkleTree::new(&awards);
+            for (index, (player, amount)) in awards.iter().enumerate() {
+                let proof = tree.proof(index);
+                let leaf = merkle_leaf(index as u32, player, *amount);
+                assert!(verify_proof(tree.root(), leaf, &proof));
+                assert!(!verify_proof(
+                    tree.root(),
+                    merkle_leaf(index as u32, player, amount + 1),
+                    &proof
+                ));
+                assert!(!verify_proof(
+                    tree.root(),
+                    merkle_leaf(index as u32 + 1, player, *amount),
+                    &proof
+                ));
+                assert!(!verify_proof(
+                    tree.root(),
+                    merkle_leaf(index as u32, &Pubkey::new_unique(), *amount),
+                    &proof
+                ));
+            }
+        }
+        assert_eq!(MerkleTree::new(&[]).root(), [0; 32]);
+    }
+
+    fn entry(id: u8, score: u64, timestamp: i64) -> RankEntry {
+        RankEntry {
+            pubkey: Pubkey::new_from_array([id; 32]),
+            score,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn rank_in_drops_the_last_slot_when_full() {
+        let mut slots = vec![entry(0, 0, 0); 2];
+        let (len, _) = rank_in(&mut slots, 0, None, entry(1, 5, 0));
+        let (len, _) = rank_in(&mut slots, len, None, entry(2, 3, 0));
+        let (len, placed) = rank_in(&mut slots, len, None, entry(3, 4, 0));
+        assert_eq!(placed, Some(entry(3, 4, 0)));
+        assert_eq!(slots[..len], [entry(1, 5, 0), entry(3, 4, 0)]);
+
+        let (len, placed) = rank_in(&mut slots, len, None, entry(2, 1, 0));
+        assert_eq!(placed, None);
+        assert_eq!(slots[..len], [entry(1, 5, 0), entry(3, 4, 0)]);
+    }
+
+    /// Rankings account data with `pages` pages of slots and `capacity` in
+    /// its header, backed by u64s so the slots are aligned like account data.
+    fn rankings_data(pages: usize, capacity: u32) -> Vec<u64> {
+        let space = Rankings::SPACE + (pages - 1) * Rankings::PAGE_SPACE;
+        let mut words = vec![0u64; space / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        let at = 8 + std::mem::offset_of!(Rankings, capacity);
+        data[at..at + 4].copy_from_slice(&capacity.to_le_bytes());
+        words
+    }
+
+    #[test]
+    fn rankings_capacity_must_match_the_slots() {
+        let mut words = rankings_data(2, 2 * RANKINGS_PAGE);
+        let (header, slots) = Rankings::split_mut(bytemuck::cast_slice_mut(&mut words)).unwrap();
+        assert_eq!(header.capacity as usize, slots.len());
+
+        // A grown account whose header wasn't updated, and the reverse.
+        let words = rankings_data(2, RANKINGS_PAGE);
+        assert!(Rankings::split(bytemuck::cast_slice(&words)).is_err());
+        let mut words = rankings_data(1, 2 * RANKINGS_PAGE);
+        assert!(Rankings::split_mut(bytemuck::cast_slice_mut(&mut words)).is_err());
+    }
+
+    /// A Token-2022 mint account listing `extensions`, each left empty.
+    fn mint_with(extensions: &[ExtensionType]) -> Vec<u8> {
+        use anchor_lang::solana_program::program_pack::Pack;
+        use anchor_spl::token_2022::spl_token_2022::extension::AccountType;
+        use anchor_spl::token_2022::spl_token_2022::state::Account as AccountState;
+
+        let mut data = vec![0; MintState::LEN];
+        MintState {
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        if extensions.is_empty() {
+            return data;
+        }
+        data.resize(AccountState::LEN, 0);
+        data.push(AccountType::Mint as u8);
+        for extension in extensions {
+            data.extend((*extension as u16).to_le_bytes());
+            data.extend(0u16.to_le_bytes());
+        }
+        data
+    }
+
+    fn supports(extensions: &[ExtensionType]) -> bool {
+        let (key, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut lamports = 0;
+        let mut data = mint_with(extensions);
+        let info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        ensure_supported_mint(&info).is_ok()
+    }
+
+    #[test]
+    fn only_harmless_mint_extensions_are_supported() {
+        assert!(supports(&[]));
+        assert!(supports(&[
+            ExtensionType::MetadataPointer,
+            ExtensionType::InterestBearingConfig
+        ]));
+        for extension in [
+            ExtensionType::PermanentDelegate,
+            ExtensionType::TransferHook,
+            ExtensionType::DefaultAccountState,
+            ExtensionType::NonTransferable,
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::ConfidentialTransferMint,
+        ] {
+            assert!(!supports(&[extension]), "{extension:?}");
+            assert!(!supports(&[ExtensionType::MetadataPointer, extension]));
+        }
+    }
 }
@@ -0,0 +1,91 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  eventPda,
+  expectError,
+  initializeEvent,
+  pointsPda,
+  predict,
+  predictionPda,
+  processResults,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Batch";
+
+describe("batched result processing", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const players = [Keypair.generate(), Keypair.generate()];
+
+  before(async () => {
+    bank = await setupBankrun(players);
+    ({ context, program, maker, season } = bank);
+  });
+
+  it("settles every pair once, even when resubmitted", async () => {
+    await initializeEvent(program, maker, season, NAME);
+
+    const oracleEvent = eventPda(program, NAME);
+    const remaining = [];
+    for (const player of players) {
+      const prediction = predictionPda(program, NAME, player.publicKey);
+      await predict(bank, NAME, player);
+      remaining.push(
+        { pubkey: prediction, isSigner: false, isWritable: true },
+        {
+          pubkey: pointsPda(program, season, player.publicKey),
+          isSigner: false,
+          isWritable: true,
+        }
+      );
+    }
+
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, NAME);
+
+    // The second submission lists the pairs in reverse so it isn't rejected
+    // as a duplicate transaction.
+    const reversed = [...remaining.slice(2), ...remaining.slice(0, 2)];
+    for (const accounts of [remaining, reversed]) {
+      await program.methods
+        .processResultsBatch()
+        .accountsPartial({ oracleEvent, global: season })
+        .remainingAccounts(accounts)
+        .rpc();
+    }
+
+    const event = await program.account.oracleEvent.fetch(oracleEvent);
+    assert.equal(event.settled.toNumber(), 2);
+    for (const player of players) {
+      const points = await program.account.playerPoints.fetch(
+        pointsPda(program, season, player.publicKey)
+      );
+      assert.equal(points.score.toNumber(), 6);
+      assert.equal(points.settled, 1);
+    }
+  });
+
+  it("records the awarded points and refuses to settle twice", async () => {
+    const [player] = players;
+    const prediction = predictionPda(program, NAME, player.publicKey);
+    const settled = await program.account.podiumPrediction.fetch(prediction);
+    assert.notEqual(settled.settledAt.toNumber(), 0);
+    assert.equal(settled.points.toNumber(), 6);
+
+    await expectError(
+      processResults(bank, NAME, player),
+      "PredictionSettled"
+    );
+  });
+});
@@ -0,0 +1,111 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  claimRefund,
+  closeSeason,
+  eventPda,
+  expectError,
+  initializeEvent,
+  lamports,
+  nextSlot,
+  pay,
+  payGlobal,
+  predict,
+  predictionPda,
+  processResults,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const CANCELLED = "Cancelled";
+const HELD = "Held";
+
+describe("event cancellation", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const players = [Keypair.generate(), Keypair.generate()];
+  const stranger = Keypair.generate();
+
+  const cancel = (resolver: Keypair) =>
+    program.methods
+      .cancelEvent(CANCELLED)
+      .accountsPartial({ resolver: resolver.publicKey, global: season })
+      .signers([resolver])
+      .rpc();
+
+  before(async () => {
+    bank = await setupBankrun([...players, stranger]);
+    ({ context, program, maker, season } = bank);
+    for (const name of [CANCELLED, HELD]) {
+      await initializeEvent(program, maker, season, name);
+      for (const player of players) {
+        await predict(bank, name, player);
+      }
+    }
+  });
+
+  it("lets only the resolver cancel and drops the event", async () => {
+    await expectError(cancel(stranger), "RequireKeysEqViolated");
+    await cancel(maker);
+
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, CANCELLED)
+    );
+    assert.isTrue(event.cancelled);
+    const { events } = await program.account.leaderboard.fetch(season);
+    assert.equal(events.toNumber(), 1);
+    await expectError(predict(bank, CANCELLED, stranger), "EventCancelled");
+  });
+
+  it("refunds each player's fee exactly once", async () => {
+    const { fee } = await program.account.oracleEvent.fetch(
+      eventPda(program, CANCELLED)
+    );
+    for (const player of players) {
+      const prediction = predictionPda(program, CANCELLED, player.publicKey);
+      const rent = await lamports(context, prediction);
+      const before = await context.banksClient.getBalance(player.publicKey);
+      await claimRefund(bank, CANCELLED, player);
+      const after = await context.banksClient.getBalance(player.publicKey);
+      // The prediction closes to its player, so its rent comes back too.
+      assert.equal(after - before, BigInt(fee.toNumber() + rent));
+    }
+
+    await nextSlot(context);
+    await expectError(
+      claimRefund(bank, CANCELLED, players[0]),
+      "AccountNotInitialized"
+    );
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, CANCELLED)
+    );
+    assert.equal(event.pool.toNumber(), 0);
+    assert.equal(event.closed.toNumber(), players.length);
+  });
+
+  it("pays the season without waiting on the cancelled event", async () => {
+    await warpTo(context, RESOLUTION_TIME);
+    await expectError(resolve(bank, CANCELLED), "EventCancelled");
+    await resolve(bank, HELD);
+    for (const player of players) {
+      await processResults(bank, HELD, player);
+    }
+    await pay(bank, HELD);
+    await closeSeason(bank);
+    await payGlobal(bank);
+
+    const global = await program.account.leaderboard.fetch(season);
+    assert.equal(global.events.toNumber(), 1);
+    assert.equal(global.settled.toNumber(), 1);
+    assert.isTrue(global.payed);
+  });
+});
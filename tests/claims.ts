@@ -0,0 +1,87 @@
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  claimPrize,
+  eventPda,
+  expectError,
+  initializeEvent,
+  nextSlot,
+  pay,
+  predict,
+  processResults,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Ten places";
+
+// The default season table, paying ten places.
+const PAYOUT = [3_500, 2_500, 1_500, 800, 500, 400, 300, 200, 200, 100];
+
+describe("prize claims", () => {
+  let bank: Bankrun;
+  const first = Keypair.generate();
+  const second = Keypair.generate();
+  const third = Keypair.generate();
+  const absent = Keypair.generate();
+
+  // Three entries of 0.1 SOL, less the season's 1% cut, shared by the three
+  // ranked players at 35/25/15 since the other seven places are empty.
+  const prizePool = (3 * LAMPORTS_PER_SOL * 99) / 1_000;
+  const prizes = [3_500, 2_500, 1_500].map((bps) => (prizePool * bps) / 7_500);
+
+  before(async () => {
+    bank = await setupBankrun([first, second, third, absent]);
+    const { context, program, maker, season } = bank;
+    await initializeEvent(program, maker, season, NAME, { payout: PAYOUT });
+    await predict(bank, NAME, first, Buffer.from([1, 2, 3]));
+    await predict(bank, NAME, second, Buffer.from([1, 3, 2]));
+    await predict(bank, NAME, third, Buffer.from([4, 5, 6]));
+
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, NAME);
+    for (const player of [first, second, third]) {
+      await processResults(bank, NAME, player);
+    }
+    await pay(bank, NAME);
+  });
+
+  it("records a prize only for the ranked players", async () => {
+    const { program } = bank;
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, NAME)
+    );
+    assert.deepEqual(
+      event.prizes.map(({ winner, amount }) => [
+        winner.toBase58(),
+        amount.toNumber(),
+      ]),
+      [first, second, third].map((player, i) => [
+        player.publicKey.toBase58(),
+        prizes[i],
+      ])
+    );
+    await expectError(claimPrize(bank, NAME, absent), "NoPrize");
+  });
+
+  it("lets each winner claim their own prize, in any order", async () => {
+    const { context, program } = bank;
+    for (const i of [2, 0, 1]) {
+      const winner = [first, second, third][i];
+      const before = await context.banksClient.getBalance(winner.publicKey);
+      await claimPrize(bank, NAME, winner);
+      const after = await context.banksClient.getBalance(winner.publicKey);
+      assert.equal(after - before, BigInt(prizes[i]));
+    }
+
+    await nextSlot(context);
+    await expectError(claimPrize(bank, NAME, first), "NoPrize");
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, NAME)
+    );
+    assert.isTrue(event.prizes.every((prize) => prize.claimed));
+  });
+});
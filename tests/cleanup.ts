@@ -0,0 +1,268 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  CLEANUP_RETENTION,
+  RESOLUTION_TIME,
+  claimPrize,
+  claimRefund,
+  cleanupReward,
+  closePlayerPoints,
+  closeSeason,
+  eventPda,
+  expectError,
+  initializeEvent,
+  lamports,
+  nextSlot,
+  pay,
+  payGlobal,
+  pointsPda,
+  predict,
+  predictionPda,
+  processResults,
+  resolve,
+  resultsPda,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const PODIUM = Buffer.from([1, 2, 3]);
+const WRONG = Buffer.from([3, 2, 1]);
+
+describe("cleanup crank", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const winner = Keypair.generate();
+  const loser = Keypair.generate();
+  const cranker = Keypair.generate();
+
+  // Creates, resolves and settles an event with one prediction per entry.
+  const settledEvent = async (
+    name: string,
+    entries: [Keypair, Buffer][]
+  ) => {
+    await warpTo(context, 0);
+    await initializeEvent(program, maker, season, name);
+    for (const [player, picks] of entries) {
+      await predict(bank, name, player, picks);
+    }
+
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, name);
+    for (const [player] of entries) {
+      await processResults(bank, name, player);
+    }
+  };
+
+  const closeOwnPrediction = (name: string, player: Keypair) =>
+    program.methods
+      .closePredictionPda()
+      .accountsPartial({
+        owner: player.publicKey,
+        oracleEvent: eventPda(program, name),
+        prediction: predictionPda(program, name, player.publicKey),
+      })
+      .signers([player])
+      .rpc();
+
+  const cleanPrediction = (name: string, player: Keypair) =>
+    program.methods
+      .cleanPrediction()
+      .accountsPartial({
+        cranker: cranker.publicKey,
+        oracleEvent: eventPda(program, name),
+        prediction: predictionPda(program, name, player.publicKey),
+        owner: player.publicKey,
+      })
+      .signers([cranker])
+      .rpc();
+
+  const closeEvent = (name: string) =>
+    program.methods
+      .closeEventPdas()
+      .accountsPartial({
+        cranker: cranker.publicKey,
+        oracleEvent: eventPda(program, name),
+        results: resultsPda(program, name),
+        maker: maker.publicKey,
+      })
+      .signers([cranker])
+      .rpc();
+
+  before(async () => {
+    bank = await setupBankrun([winner, loser, cranker]);
+    ({ context, program, maker, season } = bank);
+  });
+
+  it("won't close an event before it is paid or while predictions are open", async () => {
+    await settledEvent("Cleanup0", [
+      [winner, PODIUM],
+      [loser, WRONG],
+    ]);
+    await expectError(closeEvent("Cleanup0"), "EventNotPayed");
+
+    await pay(bank, "Cleanup0");
+    // Each retry of a rejected transaction needs a fresh blockhash.
+    await nextSlot(context);
+    await expectError(closeEvent("Cleanup0"), "OpenPredictions");
+  });
+
+  it("cranks leftover predictions only after the retention period", async () => {
+    await expectError(cleanPrediction("Cleanup0", loser), "RetentionPeriod");
+    await closeOwnPrediction("Cleanup0", winner);
+    await nextSlot(context);
+    await expectError(closeEvent("Cleanup0"), "OpenPredictions");
+
+    const { payedAt } = await program.account.oracleEvent.fetch(
+      eventPda(program, "Cleanup0")
+    );
+    await warpTo(context, payedAt.toNumber() + CLEANUP_RETENTION);
+    const rent = await lamports(
+      context,
+      predictionPda(program, "Cleanup0", loser.publicKey)
+    );
+    const reward = cleanupReward(rent);
+    const loserBefore = await context.banksClient.getBalance(loser.publicKey);
+    const crankerBefore = await context.banksClient.getBalance(
+      cranker.publicKey
+    );
+    await cleanPrediction("Cleanup0", loser);
+
+    const loserAfter = await context.banksClient.getBalance(loser.publicKey);
+    const crankerAfter = await context.banksClient.getBalance(
+      cranker.publicKey
+    );
+    assert.equal(loserAfter - loserBefore, BigInt(rent - reward));
+    assert.equal(crankerAfter - crankerBefore, BigInt(reward));
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, "Cleanup0")
+    );
+    assert.equal(event.closed.toNumber(), event.plays.toNumber());
+  });
+
+  it("won't close an event with unclaimed prizes", async () => {
+    await expectError(closeEvent("Cleanup0"), "UnclaimedPrizes");
+    await claimPrize(bank, "Cleanup0", winner);
+
+    const reward =
+      cleanupReward(await lamports(context, eventPda(program, "Cleanup0"))) +
+      cleanupReward(await lamports(context, resultsPda(program, "Cleanup0")));
+    const crankerBefore = await context.banksClient.getBalance(
+      cranker.publicKey
+    );
+    await nextSlot(context);
+    await closeEvent("Cleanup0");
+    const crankerAfter = await context.banksClient.getBalance(
+      cranker.publicKey
+    );
+    assert.equal(crankerAfter - crankerBefore, BigInt(reward));
+    assert.isNull(
+      await context.banksClient.getAccount(eventPda(program, "Cleanup0"))
+    );
+  });
+
+  it("won't close a paid event inside the retention period", async () => {
+    await settledEvent("Cleanup1", [[winner, PODIUM]]);
+    await pay(bank, "Cleanup1");
+    await claimPrize(bank, "Cleanup1", winner);
+    await closeOwnPrediction("Cleanup1", winner);
+    await expectError(closeEvent("Cleanup1"), "RetentionPeriod");
+  });
+
+  it("refunds entries a void event's players left behind", async () => {
+    const name = "Cleanup2";
+    const oracleEvent = eventPda(program, name);
+    const refund = (player: Keypair) =>
+      program.methods
+        .cleanRefund()
+        .accountsPartial({
+          cranker: cranker.publicKey,
+          oracleEvent,
+          prediction: predictionPda(program, name, player.publicKey),
+          owner: player.publicKey,
+        })
+        .signers([cranker])
+        .rpc();
+    await warpTo(context, 0);
+    await initializeEvent(program, maker, season, name);
+    for (const player of [winner, loser]) {
+      await predict(bank, name, player);
+    }
+    await program.methods
+      .cancelEvent(name)
+      .accountsPartial({ resolver: maker.publicKey, global: season })
+      .rpc();
+    await claimRefund(bank, name, winner);
+    await expectError(closeEvent(name), "OpenPredictions");
+    await expectError(refund(loser), "RetentionPeriod");
+
+    const { voidedAt, fee } = await program.account.oracleEvent.fetch(
+      oracleEvent
+    );
+    await warpTo(context, voidedAt.toNumber() + CLEANUP_RETENTION);
+    const rent = await lamports(
+      context,
+      predictionPda(program, name, loser.publicKey)
+    );
+    const before = await context.banksClient.getBalance(loser.publicKey);
+    await refund(loser);
+    const after = await context.banksClient.getBalance(loser.publicKey);
+    assert.equal(
+      after - before,
+      BigInt(fee.toNumber() + rent - cleanupReward(rent))
+    );
+
+    await closeEvent(name);
+    assert.isNull(await context.banksClient.getAccount(oracleEvent));
+  });
+
+  it("closes season points by their player, or by the crank later", async () => {
+    const points = (player: Keypair) =>
+      pointsPda(program, season, player.publicKey);
+    const cleanPoints = (player: Keypair) =>
+      program.methods
+        .cleanPlayerPoints()
+        .accountsPartial({
+          cranker: cranker.publicKey,
+          global: season,
+          playerPoints: points(player),
+          owner: player.publicKey,
+        })
+        .signers([cranker])
+        .rpc();
+    await expectError(closePlayerPoints(bank, winner), "EventNotPayed");
+    await closeSeason(bank);
+    await payGlobal(bank);
+
+    const rent = await lamports(context, points(winner));
+    const before = await context.banksClient.getBalance(winner.publicKey);
+    await nextSlot(context);
+    await closePlayerPoints(bank, winner);
+    const after = await context.banksClient.getBalance(winner.publicKey);
+    assert.equal(after - before, BigInt(rent));
+    assert.isNull(await context.banksClient.getAccount(points(winner)));
+
+    await expectError(cleanPoints(loser), "RetentionPeriod");
+    const { payedAt, players } = await program.account.leaderboard.fetch(
+      season
+    );
+    assert.equal(players, 1);
+    await warpTo(context, payedAt.toNumber() + CLEANUP_RETENTION);
+    const loserRent = await lamports(context, points(loser));
+    const loserBefore = await context.banksClient.getBalance(loser.publicKey);
+    await cleanPoints(loser);
+    const loserAfter = await context.banksClient.getBalance(loser.publicKey);
+    assert.equal(
+      loserAfter - loserBefore,
+      BigInt(loserRent - cleanupReward(loserRent))
+    );
+    const global = await program.account.leaderboard.fetch(season);
+    assert.equal(global.players, 0);
+  });
+});
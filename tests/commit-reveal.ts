@@ -0,0 +1,150 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import { createHash, randomBytes } from "crypto";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  PICKS,
+  RESOLUTION_TIME,
+  eventPda,
+  expectError,
+  initializeEvent,
+  pointsPda,
+  predict,
+  predictionPda,
+  processResults,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Sealed";
+const OPEN_UNTIL = RESOLUTION_TIME - 100;
+const REVEAL_UNTIL = RESOLUTION_TIME;
+
+// Matches commitment in the program.
+const commitment = (picks: Buffer, salt: Buffer, player: PublicKey) => [
+  ...createHash("sha256")
+    .update(Buffer.concat([picks, salt, player.toBuffer()]))
+    .digest(),
+];
+
+describe("commit-reveal predictions", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const revealer = Keypair.generate();
+  const forgetter = Keypair.generate();
+  const salts = new Map(
+    [revealer, forgetter].map((player) => [player, randomBytes(32)])
+  );
+
+  const enter = (player: Keypair) => ({
+    player: player.publicKey,
+    oracleEvent: eventPda(program, NAME),
+    prediction: predictionPda(program, NAME, player.publicKey),
+    feeReceiver: maker.publicKey,
+    global: season,
+  });
+
+  const reveal = (player: Keypair, salt = salts.get(player)) =>
+    program.methods
+      .revealPrediction(NAME, PICKS, [...salt])
+      .accountsPartial({
+        player: player.publicKey,
+        oracleEvent: eventPda(program, NAME),
+        prediction: predictionPda(program, NAME, player.publicKey),
+      })
+      .signers([player])
+      .rpc();
+
+  before(async () => {
+    bank = await setupBankrun([revealer, forgetter]);
+    ({ context, program, maker, season } = bank);
+    await initializeEvent(program, maker, season, NAME, {
+      openUntilTime: OPEN_UNTIL,
+      revealUntil: REVEAL_UNTIL,
+    });
+    for (const player of [revealer, forgetter]) {
+      await program.methods
+        .commitPrediction(
+          NAME,
+          commitment(PICKS, salts.get(player), player.publicKey)
+        )
+        .accountsPartial(enter(player))
+        .signers([player])
+        .rpc();
+    }
+  });
+
+  it("hides the picks and takes no plain predictions", async () => {
+    const prediction = await program.account.podiumPrediction.fetch(
+      predictionPda(program, NAME, revealer.publicKey)
+    );
+    assert.equal(prediction.picks.length, 0);
+
+    const player = Keypair.generate();
+    context.setAccount(player.publicKey, {
+      lamports: LAMPORTS_PER_SOL,
+      data: Buffer.alloc(0),
+      owner: SystemProgram.programId,
+      executable: false,
+    });
+    await expectError(predict(bank, NAME, player), "WrongPredictionMode");
+  });
+
+  it("takes reveals only after predictions close", async () => {
+    await expectError(reveal(revealer), "RevealWindowClosed");
+    await warpTo(context, OPEN_UNTIL);
+    await expectError(reveal(revealer), "RevealWindowClosed");
+  });
+
+  it("rejects a reveal with the wrong salt", async () => {
+    await warpTo(context, OPEN_UNTIL + 1);
+    await expectError(reveal(revealer, randomBytes(32)), "InvalidReveal");
+    await reveal(revealer);
+
+    const prediction = await program.account.podiumPrediction.fetch(
+      predictionPda(program, NAME, revealer.publicKey)
+    );
+    assert.deepEqual([...prediction.picks], [...PICKS]);
+  });
+
+  it("closes reveals at reveal_until", async () => {
+    await warpTo(context, REVEAL_UNTIL + 1);
+    await expectError(reveal(forgetter), "RevealWindowClosed");
+  });
+
+  it("scores an unrevealed commitment zero", async () => {
+    await resolve(bank, NAME);
+    for (const player of [revealer, forgetter]) {
+      await processResults(bank, NAME, player);
+    }
+
+    const score = async (player: Keypair) => {
+      const points = await program.account.playerPoints.fetch(
+        pointsPda(program, season, player.publicKey)
+      );
+      return points.score.toNumber();
+    };
+    assert.equal(await score(revealer), 6);
+    assert.equal(await score(forgetter), 0);
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, NAME)
+    );
+    assert.equal(event.settled.toNumber(), 2);
+    assert.deepEqual(
+      event.leaderboard.map((player) => player.pubkey.toBase58()),
+      [revealer.publicKey.toBase58()]
+    );
+  });
+});
@@ -0,0 +1,101 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  FEES,
+  expectError,
+  nextSlot,
+  pda,
+  setUpgradeAuthority,
+  setupBankrun,
+} from "./helpers";
+
+describe("config", () => {
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let admin: Keypair;
+  let programData: PublicKey;
+  const stranger = Keypair.generate();
+  const successor = Keypair.generate();
+  const config = () => pda(program, Buffer.from("config"));
+
+  const initConfig = (authority: Keypair) =>
+    program.methods
+      .initConfig(authority.publicKey, FEES)
+      .accountsPartial({ authority: authority.publicKey, programData })
+      .signers([authority])
+      .rpc();
+
+  const updateConfig = (authority: Keypair, treasury: PublicKey) =>
+    program.methods
+      .updateConfig(treasury, FEES)
+      .accounts({ authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const transferAuthority = (authority: Keypair) =>
+    program.methods
+      .transferAuthority(successor.publicKey)
+      .accounts({ authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const acceptAuthority = (pendingAuthority: Keypair) =>
+    program.methods
+      .acceptAuthority()
+      .accounts({ pendingAuthority: pendingAuthority.publicKey })
+      .signers([pendingAuthority])
+      .rpc();
+
+  before(async () => {
+    ({ context, program, maker: admin } = await setupBankrun(
+      [stranger, successor],
+      { config: false }
+    ));
+    programData = await setUpgradeAuthority(context, program, admin.publicKey);
+  });
+
+  it("only lets the upgrade authority create the config", async () => {
+    await expectError(initConfig(stranger), "Unauthorized");
+    await initConfig(admin);
+
+    const account = await program.account.config.fetch(config());
+    assert.isTrue(account.authority.equals(admin.publicKey));
+    assert.isTrue(account.pendingAuthority.equals(PublicKey.default));
+  });
+
+  it("rejects config updates from anyone but the authority", async () => {
+    await expectError(
+      updateConfig(stranger, stranger.publicKey),
+      "RequireKeysEqViolated"
+    );
+    await updateConfig(admin, successor.publicKey);
+
+    const account = await program.account.config.fetch(config());
+    assert.isTrue(account.treasury.equals(successor.publicKey));
+  });
+
+  it("hands the authority over only once the new one accepts", async () => {
+    await expectError(transferAuthority(stranger), "RequireKeysEqViolated");
+    await transferAuthority(admin);
+
+    let account = await program.account.config.fetch(config());
+    assert.isTrue(account.authority.equals(admin.publicKey));
+    assert.isTrue(account.pendingAuthority.equals(successor.publicKey));
+    await expectError(acceptAuthority(stranger), "RequireKeysEqViolated");
+
+    await acceptAuthority(successor);
+    account = await program.account.config.fetch(config());
+    assert.isTrue(account.authority.equals(successor.publicKey));
+    assert.isTrue(account.pendingAuthority.equals(PublicKey.default));
+
+    await nextSlot(context);
+    await expectError(
+      updateConfig(admin, admin.publicKey),
+      "RequireKeysEqViolated"
+    );
+    await updateConfig(successor, admin.publicKey);
+  });
+});
@@ -0,0 +1,73 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  expectError,
+  initializeEvent,
+  nextSlot,
+  pda,
+  setupBankrun,
+} from "./helpers";
+
+describe("event creators", () => {
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let admin: Keypair;
+  let season: PublicKey;
+  const creator = Keypair.generate();
+  const approval = () =>
+    pda(program, Buffer.from("creator"), creator.publicKey.toBuffer());
+
+  const approve = (authority: Keypair) =>
+    program.methods
+      .approveCreator(creator.publicKey)
+      .accounts({ authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  const revoke = (authority: Keypair) =>
+    program.methods
+      .revokeCreator(creator.publicKey)
+      .accounts({ authority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    ({
+      context,
+      program,
+      maker: admin,
+      season,
+    } = await setupBankrun([creator]));
+  });
+
+  it("rejects events from a creator who was never approved", async () => {
+    await expectError(
+      initializeEvent(program, creator, season, "Unapproved"),
+      "AccountNotInitialized"
+    );
+  });
+
+  it("only lets the config authority approve a creator", async () => {
+    await expectError(approve(creator), "RequireKeysEqViolated");
+    await approve(admin);
+
+    const account = await program.account.creatorApproval.fetch(approval());
+    assert.isTrue(account.creator.equals(creator.publicKey));
+    await initializeEvent(program, creator, season, "Approved");
+  });
+
+  it("stops a revoked creator from opening more events", async () => {
+    await expectError(revoke(creator), "RequireKeysEqViolated");
+    await revoke(admin);
+
+    assert.isNull(await context.banksClient.getAccount(approval()));
+    await nextSlot(context);
+    await expectError(
+      initializeEvent(program, creator, season, "Revoked"),
+      "AccountNotInitialized"
+    );
+  });
+});
@@ -0,0 +1,180 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  eventPda,
+  expectError,
+  initializeEvent,
+  lamports,
+  pda,
+  pointsPda,
+  predict,
+  predictionPda,
+  processResults,
+  resolve,
+  resultsPda,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+// Matches MIN_DISPUTE_BOND in the program.
+const MIN_DISPUTE_BOND = new BN(10_000_000);
+const DISPUTE_PERIOD = 100;
+const UPHELD = "Upheld";
+const DISMISSED = "Dismissed";
+const AMENDED = [Buffer.from([3]), Buffer.from([2]), Buffer.from([1])];
+
+describe("disputes", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const player = Keypair.generate();
+  const arbitrator = Keypair.generate();
+
+  const disputePda = (name: string) =>
+    pda(
+      program,
+      Buffer.from("Dispute"),
+      Buffer.from(name),
+      player.publicKey.toBuffer()
+    );
+
+  const openDispute = (name: string) =>
+    program.methods
+      .openDispute(name)
+      .accountsPartial({
+        player: player.publicKey,
+        oracleEvent: eventPda(program, name),
+        prediction: predictionPda(program, name, player.publicKey),
+        dispute: disputePda(name),
+      })
+      .signers([player])
+      .rpc();
+
+  const amendResults = (name: string, signer: Keypair) =>
+    program.methods
+      .amendResults(name, AMENDED)
+      .accountsPartial({ arbitrator: signer.publicKey })
+      .signers([signer])
+      .rpc();
+
+  const settleDispute = (name: string, upheld: boolean) =>
+    program.methods
+      .settleDispute(name, upheld)
+      .accountsPartial({
+        arbitrator: arbitrator.publicKey,
+        dispute: disputePda(name),
+        player: player.publicKey,
+        projectTreasury: maker.publicKey,
+      })
+      .signers([arbitrator])
+      .rpc();
+
+  const disputedEvent = (name: string, disputeBond = MIN_DISPUTE_BOND) =>
+    initializeEvent(program, maker, season, name, {
+      arbitrator: arbitrator.publicKey,
+      disputePeriod: DISPUTE_PERIOD,
+      disputeBond,
+    });
+
+  before(async () => {
+    bank = await setupBankrun([player, arbitrator]);
+    ({ context, program, maker, season } = bank);
+    for (const name of [UPHELD, DISMISSED]) {
+      await disputedEvent(name);
+      await predict(bank, name, player);
+    }
+    await warpTo(context, RESOLUTION_TIME);
+    for (const name of [UPHELD, DISMISSED]) {
+      await resolve(bank, name);
+    }
+  });
+
+  it("rejects a dispute period without the minimum bond", async () => {
+    await expectError(
+      disputedEvent("Free", new BN(0)),
+      "InvalidDisputeConfig"
+    );
+    await expectError(
+      disputedEvent("Cheap", MIN_DISPUTE_BOND.subn(1)),
+      "InvalidDisputeConfig"
+    );
+  });
+
+  it("forfeits a dismissed dispute's bond", async () => {
+    await openDispute(DISMISSED);
+    const held = await lamports(context, disputePda(DISMISSED));
+
+    const before = await lamports(context, player.publicKey);
+    await settleDispute(DISMISSED, false);
+    const after = await lamports(context, player.publicKey);
+    assert.equal(after - before, held - MIN_DISPUTE_BOND.toNumber());
+  });
+
+  it("holds the bond and blocks processing while disputed", async () => {
+    const before = await lamports(context, player.publicKey);
+    await openDispute(UPHELD);
+    const held = await lamports(context, disputePda(UPHELD));
+    const after = await lamports(context, player.publicKey);
+    assert.equal(before - after, held);
+    const dispute = await program.account.dispute.fetch(disputePda(UPHELD));
+    assert.isTrue(dispute.bond.eq(MIN_DISPUTE_BOND));
+
+    await warpTo(context, RESOLUTION_TIME + DISPUTE_PERIOD);
+    await expectError(
+      processResults(bank, UPHELD, player),
+      "DisputeWindowOpen"
+    );
+  });
+
+  it("records each amendment's podium before and after", async () => {
+    await expectError(amendResults(UPHELD, player), "RequireKeysEqViolated");
+    await amendResults(UPHELD, arbitrator);
+
+    const { amendments } = await program.account.eventResults.fetch(
+      resultsPda(program, UPHELD)
+    );
+    assert.equal(amendments.length, 1);
+    const places = (podium: { places: Buffer[] }) =>
+      podium.places.map((place) => [...place]);
+    assert.deepEqual(places(amendments[0].before), [[1], [2], [3]]);
+    assert.deepEqual(places(amendments[0].after), [[3], [2], [1]]);
+    assert.equal(
+      amendments[0].amendedAt.toNumber(),
+      RESOLUTION_TIME + DISPUTE_PERIOD
+    );
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, UPHELD)
+    );
+    assert.deepEqual(places(event.podium), [[3], [2], [1]]);
+  });
+
+  it("refunds an upheld dispute and scores the amendment", async () => {
+    const before = await lamports(context, player.publicKey);
+    const held = await lamports(context, disputePda(UPHELD));
+    await settleDispute(UPHELD, true);
+    assert.equal(await lamports(context, player.publicKey), before + held);
+    assert.isNull(await context.banksClient.getAccount(disputePda(UPHELD)));
+
+    await processResults(bank, UPHELD, player);
+    const points = await program.account.playerPoints.fetch(
+      pointsPda(program, season, player.publicKey)
+    );
+    // Only the silver pick still matches.
+    assert.equal(points.score.toNumber(), 2);
+  });
+
+  it("closes amendments once the window passes undisputed", async () => {
+    await expectError(
+      amendResults(DISMISSED, arbitrator),
+      "DisputeWindowClosed"
+    );
+    await processResults(bank, DISMISSED, player);
+  });
+});
@@ -0,0 +1,79 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  RESOLUTION_TIME,
+  closeSeason,
+  expectError,
+  initializeEvent,
+  merkleLeaf,
+  nextSlot,
+  pay,
+  pda,
+  predict,
+  processResults,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Distribution";
+
+describe("merkle distribution", () => {
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const player = Keypair.generate();
+
+  const claim = (amount: BN) =>
+    program.methods
+      .claimDistribution(0, amount, [])
+      .accountsPartial({ winner: player.publicKey, global: season })
+      .signers([player])
+      .rpc();
+
+  before(async () => {
+    const bank = await setupBankrun([player]);
+    ({ context, program, maker, season } = bank);
+    await initializeEvent(program, maker, season, NAME);
+    await predict(bank, NAME, player);
+
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, NAME);
+    await processResults(bank, NAME, player);
+    await pay(bank, NAME);
+    await closeSeason(bank);
+  });
+
+  it("pays each leaf once against the posted root", async () => {
+    const { pool } = await program.account.leaderboard.fetch(season);
+    await expectError(
+      program.methods
+        .postDistribution(merkleLeaf(0, player.publicKey, pool), pool.addn(1), 1)
+        .accountsPartial({ authority: maker.publicKey, global: season })
+        .rpc(),
+      "InvalidDistribution"
+    );
+    await program.methods
+      .postDistribution(merkleLeaf(0, player.publicKey, pool), pool, 1)
+      .accountsPartial({ authority: maker.publicKey, global: season })
+      .rpc();
+    assert.isTrue((await program.account.leaderboard.fetch(season)).payed);
+
+    await expectError(claim(pool.addn(1)), "InvalidProof");
+    const before = await context.banksClient.getBalance(player.publicKey);
+    await claim(pool);
+    const after = await context.banksClient.getBalance(player.publicKey);
+    assert.equal(after - before, BigInt(pool.toString()));
+
+    await nextSlot(context);
+    await expectError(claim(pool), "NoPrize");
+    const distribution = await program.account.merkleDistribution.fetch(
+      pda(program, Buffer.from("distribution"), season.toBuffer())
+    );
+    assert.isTrue(distribution.claimed.eq(pool));
+  });
+});
@@ -0,0 +1,441 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { BankrunProvider, startAnchor } from "anchor-bankrun";
+import { Clock, ProgramTestContext } from "solana-bankrun";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+
+const IDL = require("../target/idl/potrero_olimpcs.json");
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+// Moving to a new slot also gets a fresh blockhash, so a transaction retried
+// after a warp isn't rejected as a duplicate.
+export const warpTo = async (
+  context: ProgramTestContext,
+  unixTimestamp: number
+) => {
+  const clock = await context.banksClient.getClock();
+  const slot = clock.slot + BigInt(1);
+  context.warpToSlot(slot);
+  context.setClock(
+    new Clock(
+      slot,
+      clock.epochStartTimestamp,
+      clock.epoch,
+      clock.leaderScheduleEpoch,
+      BigInt(unixTimestamp)
+    )
+  );
+};
+
+export const nextSlot = async (context: ProgramTestContext) => {
+  const clock = await context.banksClient.getClock();
+  await warpTo(context, Number(clock.unixTimestamp));
+};
+
+export const expectError = async (promise: Promise<unknown>, code: string) => {
+  try {
+    await promise;
+  } catch (err) {
+    assert.include(String(err), code);
+    return;
+  }
+  assert.fail(`expected ${code}`);
+};
+
+export const SEASON = "Test season";
+export const RESOLUTION_TIME = 1_000_000;
+export const CLEANUP_RETENTION = 30 * 24 * 60 * 60;
+
+// Matches cleanup_reward in the program for an account holding just its
+// rent.
+export const cleanupReward = (rent: number) => Math.floor(rent / 10);
+
+type FeeRates = {
+  creatorBasisPoints: number;
+  globalBasisPoints: number;
+  treasuryBasisPoints: number;
+};
+
+// The Config's fees in every test: only the season takes a 1% cut.
+export const FEES: FeeRates = {
+  creatorBasisPoints: 0,
+  globalBasisPoints: 100,
+  treasuryBasisPoints: 0,
+};
+
+// The picks that call the whole default podium, and that podium as
+// `resolve` takes it.
+export const PICKS = Buffer.from([1, 2, 3]);
+export const PLACES = [Buffer.from([1]), Buffer.from([2]), Buffer.from([3])];
+
+type Accounts = Record<string, PublicKey>;
+
+export const pda = (program: Program<PotreroOlimpcs>, ...seeds: Buffer[]) =>
+  PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+export const eventPda = (program: Program<PotreroOlimpcs>, name: string) =>
+  pda(program, Buffer.from("OracleEvent"), Buffer.from(name));
+
+export const resultsPda = (program: Program<PotreroOlimpcs>, name: string) =>
+  pda(program, Buffer.from("EventResults"), Buffer.from(name));
+
+export const predictionPda = (
+  program: Program<PotreroOlimpcs>,
+  name: string,
+  player: PublicKey
+) =>
+  pda(
+    program,
+    Buffer.from("PodiumPrediction"),
+    eventPda(program, name).toBuffer(),
+    player.toBuffer()
+  );
+
+export const lamports = async (
+  context: ProgramTestContext,
+  address: PublicKey
+) => (await context.banksClient.getAccount(address)).lamports;
+
+export const pointsPda = (
+  program: Program<PotreroOlimpcs>,
+  season: PublicKey,
+  player: PublicKey
+) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("points"), season.toBuffer(), player.toBuffer()],
+    program.programId
+  )[0];
+
+// Matches merkle_leaf in the program. With a single claimant the leaf is
+// the root and the proof is empty.
+export const merkleLeaf = (index: number, player: PublicKey, amount: BN) => {
+  const data = Buffer.alloc(1 + 4 + 32 + 8);
+  data.writeUInt32LE(index, 1);
+  player.toBuffer().copy(data, 5);
+  amount.toArrayLike(Buffer, "le", 8).copy(data, 37);
+  return [...createHash("sha256").update(data).digest()];
+};
+
+// The arguments of `initialize` after the name. The defaults describe a
+// three-place SOL event resolved by its maker at RESOLUTION_TIME, with the
+// Config's fees and no grace, dispute or reveal periods.
+export type EventOptions = {
+  positions: number;
+  resolutionTime: number;
+  openUntilTime: number;
+  resolver: PublicKey;
+  fee: BN;
+  feeReceiver: PublicKey;
+  fees: FeeRates | null;
+  gracePeriod: number | BN;
+  backupResolver: PublicKey;
+  arbitrator: PublicKey;
+  disputePeriod: number;
+  disputeBond: BN;
+  scoring: { exact: number[]; onPodium: number; perfectBonus: number };
+  payout: number[];
+  withdrawPenalty: number;
+  revealUntil: number;
+  accounts: Accounts;
+};
+
+export const initializeEvent = (
+  program: Program<PotreroOlimpcs>,
+  maker: Keypair,
+  season: PublicKey,
+  name: string,
+  options: Partial<EventOptions> = {}
+) => {
+  const event: EventOptions = {
+    positions: 3,
+    resolutionTime: RESOLUTION_TIME,
+    openUntilTime: RESOLUTION_TIME - 1,
+    resolver: maker.publicKey,
+    fee: new BN(LAMPORTS_PER_SOL / 10),
+    feeReceiver: maker.publicKey,
+    fees: null,
+    gracePeriod: 0,
+    backupResolver: PublicKey.default,
+    arbitrator: PublicKey.default,
+    disputePeriod: 0,
+    disputeBond: new BN(0),
+    scoring: { exact: [3, 2, 1], onPodium: 0, perfectBonus: 0 },
+    payout: [10_000],
+    withdrawPenalty: 0,
+    revealUntil: 0,
+    accounts: {},
+    ...options,
+  };
+  return program.methods
+    .initialize(
+      name,
+      event.positions,
+      new BN(event.resolutionTime),
+      new BN(event.openUntilTime),
+      event.resolver,
+      event.fee,
+      event.feeReceiver,
+      event.fees,
+      new BN(event.gracePeriod),
+      event.backupResolver,
+      event.arbitrator,
+      new BN(event.disputePeriod),
+      event.disputeBond,
+      event.scoring,
+      event.payout,
+      event.withdrawPenalty,
+      new BN(event.revealUntil)
+    )
+    .accountsPartial({
+      maker: maker.publicKey,
+      global: season,
+      ...event.accounts,
+    })
+    .signers([maker])
+    .rpc();
+};
+
+// Points the program's ProgramData account at `authority` as its upgrade
+// authority, which init_config requires, and returns its address.
+export const setUpgradeAuthority = async (
+  context: ProgramTestContext,
+  program: Program<PotreroOlimpcs>,
+  authority: PublicKey
+) => {
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE
+  );
+  const account = await context.banksClient.getAccount(programData);
+  const data = Buffer.from(account.data);
+  data[12] = 1;
+  authority.toBuffer().copy(data, 13);
+  context.setAccount(programData, { ...account, data });
+  return programData;
+};
+
+// Creates the Config, approves `authority` as an event creator and opens
+// SEASON, returning its leaderboard.
+const bootstrap = async (
+  context: ProgramTestContext,
+  program: Program<PotreroOlimpcs>,
+  authority: Keypair
+) => {
+  const programData = await setUpgradeAuthority(
+    context,
+    program,
+    authority.publicKey
+  );
+  await program.methods
+    .initConfig(authority.publicKey, FEES)
+    .accountsPartial({ authority: authority.publicKey, programData })
+    .signers([authority])
+    .rpc();
+  await program.methods
+    .approveCreator(authority.publicKey)
+    .accounts({ authority: authority.publicKey })
+    .signers([authority])
+    .rpc();
+  await program.methods
+    .openSeason(SEASON)
+    .accounts({ authority: authority.publicKey })
+    .signers([authority])
+    .rpc();
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("season"), Buffer.from(SEASON)],
+    program.programId
+  )[0];
+};
+
+export type Bankrun = {
+  context: ProgramTestContext;
+  provider: BankrunProvider;
+  program: Program<PotreroOlimpcs>;
+  // The bank's payer. It is the Config authority and treasury, an approved
+  // creator and the maker and fee receiver of every event.
+  maker: Keypair;
+  season: PublicKey;
+};
+
+// Starts a bank with the program deployed and gives each of `funded` 1 SOL.
+// Unless `config` is false it also creates the Config and opens SEASON. The
+// clock starts at 0, so new events take predictions.
+export const setupBankrun = async (
+  funded: Keypair[] = [],
+  { config = true } = {}
+): Promise<Bankrun> => {
+  const context = await startAnchor("", [], []);
+  const provider = new BankrunProvider(context);
+  anchor.setProvider(provider);
+  const program = new Program<PotreroOlimpcs>(IDL, provider);
+  const maker = context.payer;
+
+  if (funded.length > 0) {
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        ...funded.map((keypair) =>
+          SystemProgram.transfer({
+            fromPubkey: maker.publicKey,
+            toPubkey: keypair.publicKey,
+            lamports: LAMPORTS_PER_SOL,
+          })
+        )
+      )
+    );
+  }
+  const season = config
+    ? await bootstrap(context, program, maker)
+    : PublicKey.default;
+  await warpTo(context, 0);
+  return { context, provider, program, maker, season };
+};
+
+// The instructions most tests run, signed by the player they act for and
+// otherwise by the maker. `accounts` adds or overrides accounts, such as
+// the token accounts of a token event.
+
+export const predict = (
+  { program, maker, season }: Bankrun,
+  name: string,
+  player: Keypair,
+  picks = PICKS,
+  accounts: Accounts = {}
+) =>
+  program.methods
+    .makePrediction(name, picks)
+    .accountsPartial({
+      player: player.publicKey,
+      oracleEvent: eventPda(program, name),
+      prediction: predictionPda(program, name, player.publicKey),
+      feeReceiver: maker.publicKey,
+      global: season,
+      ...accounts,
+    })
+    .signers([player])
+    .rpc();
+
+export const resolve = (
+  { program, maker }: Bankrun,
+  name: string,
+  places = PLACES,
+  resolver = maker
+) =>
+  program.methods
+    .resolve(places)
+    .accounts({
+      resolver: resolver.publicKey,
+      oracleEvent: eventPda(program, name),
+    })
+    .signers([resolver])
+    .rpc();
+
+export const processResults = (
+  { program, season }: Bankrun,
+  name: string,
+  player: Keypair
+) =>
+  program.methods
+    .processResults()
+    .accountsPartial({
+      oracleEvent: eventPda(program, name),
+      prediction: predictionPda(program, name, player.publicKey),
+      playerPoints: pointsPda(program, season, player.publicKey),
+      global: season,
+    })
+    .rpc();
+
+export const pay = (
+  { program, maker, season }: Bankrun,
+  name: string,
+  accounts: Accounts = {}
+) =>
+  program.methods
+    .pay(name)
+    .accountsPartial({
+      player: maker.publicKey,
+      oracleEvent: eventPda(program, name),
+      feeReceiver: maker.publicKey,
+      projectTreasury: maker.publicKey,
+      global: season,
+      ...accounts,
+    })
+    .rpc();
+
+export const claimPrize = (
+  { program }: Bankrun,
+  name: string,
+  winner: Keypair,
+  accounts: Accounts = {}
+) =>
+  program.methods
+    .claimPrize(name)
+    .accountsPartial({
+      winner: winner.publicKey,
+      oracleEvent: eventPda(program, name),
+      ...accounts,
+    })
+    .signers([winner])
+    .rpc();
+
+export const claimRefund = (
+  { program, season }: Bankrun,
+  name: string,
+  player: Keypair
+) =>
+  program.methods
+    .claimRefund(name)
+    .accountsPartial({
+      player: player.publicKey,
+      prediction: predictionPda(program, name, player.publicKey),
+      playerPoints: pointsPda(program, season, player.publicKey),
+    })
+    .signers([player])
+    .rpc();
+
+export const closeSeason = (
+  { program, maker, season }: Bankrun,
+  global = season
+) =>
+  program.methods
+    .closeSeason()
+    .accountsPartial({ authority: maker.publicKey, global })
+    .rpc();
+
+export const payGlobal = (
+  { program, maker, season }: Bankrun,
+  accounts: Accounts = {}
+) =>
+  program.methods
+    .payGlobal()
+    .accountsPartial({
+      global: season,
+      projectTreasury: maker.publicKey,
+      ...accounts,
+    })
+    .rpc();
+
+export const closePlayerPoints = (
+  { program, season }: Bankrun,
+  player: Keypair
+) =>
+  program.methods
+    .closePlayerPoints()
+    .accountsPartial({
+      player: player.publicKey,
+      global: season,
+      playerPoints: pointsPda(program, season, player.publicKey),
+    })
+    .signers([player])
+    .rpc();
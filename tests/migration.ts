@@ -0,0 +1,213 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { createHash } from "crypto";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  eventPda,
+  expectError,
+  lamports,
+  nextSlot,
+  pda,
+  pointsPda,
+  predictionPda,
+  processResults,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Legacy";
+const FEE = new BN(LAMPORTS_PER_SOL / 10);
+const LEGACY_EVENT_SPACE = 1_000;
+
+const discriminator = (account: string) =>
+  createHash("sha256").update(`account:${account}`).digest().subarray(0, 8);
+
+const u8 = (value: number) => Buffer.from([value]);
+const u16 = (value: number) => new BN(value).toArrayLike(Buffer, "le", 2);
+const i64 = (value: number) => new BN(value).toArrayLike(Buffer, "le", 8);
+const u128 = (value: number) => new BN(value).toArrayLike(Buffer, "le", 16);
+
+describe("legacy migration", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const player = Keypair.generate();
+  const oracleEvent = () => eventPda(program, NAME);
+  const legacyPrediction = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("PodiumPrediction"), Buffer.from(NAME), u128(index)],
+      program.programId
+    );
+  const lock = () =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("lock"), Buffer.from(NAME), player.publicKey.toBuffer()],
+      program.programId
+    );
+
+  // Writes a program-owned account the way the first deployment laid it out.
+  const setLegacyAccount = async (address: PublicKey, data: Buffer) => {
+    const rent = await context.banksClient.getRent();
+    context.setAccount(address, {
+      lamports: Number(rent.minimumBalance(BigInt(data.length))),
+      data,
+      owner: program.programId,
+      executable: false,
+    });
+  };
+
+  // A resolved event with one prediction, as the first deployment left it
+  // after processing `settled` of them and paying out if `payed`.
+  const setLegacyEvent = async (name: string, settled = 0, payed = false) => {
+    const address = eventPda(program, name);
+    const [, eventBump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("OracleEvent"), Buffer.from(name)],
+      program.programId
+    );
+    const event = Buffer.concat([
+      discriminator("OracleEvent"),
+      i64(RESOLUTION_TIME - 1),
+      i64(RESOLUTION_TIME),
+      i64(RESOLUTION_TIME),
+      maker.publicKey.toBuffer(),
+      FEE.toArrayLike(Buffer, "le", 8),
+      u16(0),
+      maker.publicKey.toBuffer(),
+      u128(1),
+      u128(settled),
+      u8(payed ? 1 : 0),
+      u8(eventBump),
+      u8(1),
+      u8(2),
+      u8(3),
+      Buffer.from([0, 0, 0, 0]),
+      Buffer.from([name.length, 0, 0, 0]),
+      Buffer.from(name),
+    ]);
+    const eventData = Buffer.alloc(LEGACY_EVENT_SPACE);
+    event.copy(eventData);
+    await setLegacyAccount(address, eventData);
+    if (!payed) {
+      const account = await context.banksClient.getAccount(address);
+      context.setAccount(address, {
+        ...account,
+        lamports: account.lamports + FEE.toNumber(),
+      });
+    }
+  };
+
+  const migrateEvent = (name: string) =>
+    program.methods
+      .migrateEvent(name)
+      .accountsPartial({ authority: maker.publicKey, global: season })
+      .rpc();
+
+  // The legacy prediction was made at index 0. The maker cranks it, so the
+  // player only ever receives lamports.
+  const migratePrediction = (index: number) =>
+    program.methods
+      .migratePrediction(NAME, new BN(index))
+      .accountsPartial({
+        cranker: maker.publicKey,
+        player: player.publicKey,
+        legacyPrediction: legacyPrediction(0)[0],
+        lock: lock()[0],
+        global: season,
+      })
+      .rpc();
+
+  before(async () => {
+    bank = await setupBankrun();
+    ({ context, program, maker, season } = bank);
+
+    await setLegacyEvent(NAME);
+
+    const [prediction, predictionBump] = legacyPrediction(0);
+    await setLegacyAccount(
+      prediction,
+      Buffer.concat([
+        discriminator("PodiumPrediction"),
+        Buffer.from([1, 2, 3]),
+        oracleEvent().toBuffer(),
+        i64(RESOLUTION_TIME - 2),
+        player.publicKey.toBuffer(),
+        u8(predictionBump),
+      ])
+    );
+    const [lockAddress, lockBump] = lock();
+    await setLegacyAccount(
+      lockAddress,
+      Buffer.concat([discriminator("Lock"), u8(lockBump)])
+    );
+    await warpTo(context, RESOLUTION_TIME + 1);
+  });
+
+  it("refuses legacy events that were already processed or paid", async () => {
+    await setLegacyEvent("Partly processed", 1);
+    await setLegacyEvent("Paid", 1, true);
+    await expectError(
+      migrateEvent("Partly processed"),
+      "LegacyEventProcessed"
+    );
+    await expectError(migrateEvent("Paid"), "LegacyEventProcessed");
+
+    const { events } = await program.account.leaderboard.fetch(season);
+    assert.equal(events.toNumber(), 0);
+  });
+
+  it("rewrites a legacy event into the current layout", async () => {
+    await migrateEvent(NAME);
+
+    const event = await program.account.oracleEvent.fetch(oracleEvent());
+    assert.equal(event.positions, 3);
+    assert.equal(event.plays.toNumber(), 1);
+    assert.isTrue(event.pool.eq(FEE));
+    assert.isTrue(event.season.equals(season));
+    assert.equal(event.solvedAt.toNumber(), RESOLUTION_TIME);
+    assert.deepEqual(
+      event.podium.places.map((place) => [...place]),
+      [[1], [2], [3]]
+    );
+    const { events } = await program.account.leaderboard.fetch(season);
+    assert.equal(events.toNumber(), 1);
+    assert.isNotNull(
+      await context.banksClient.getAccount(
+        pda(program, Buffer.from("EventResults"), Buffer.from(NAME))
+      )
+    );
+  });
+
+  it("moves a legacy prediction to its player's address", async () => {
+    await expectError(migratePrediction(1), "ConstraintSeeds");
+    const oldRent =
+      (await lamports(context, legacyPrediction(0)[0])) +
+      (await lamports(context, lock()[0]));
+    await nextSlot(context);
+    await migratePrediction(0);
+    assert.equal(await lamports(context, player.publicKey), oldRent);
+
+    const prediction = await program.account.podiumPrediction.fetch(
+      predictionPda(program, NAME, player.publicKey)
+    );
+    assert.deepEqual([...prediction.picks], [1, 2, 3]);
+    assert.isNull(await context.banksClient.getAccount(legacyPrediction(0)[0]));
+    assert.isNull(await context.banksClient.getAccount(lock()[0]));
+    const points = await program.account.playerPoints.fetch(
+      pointsPda(program, season, player.publicKey)
+    );
+    assert.equal(points.events, 1);
+  });
+
+  it("scores a migrated prediction like any other", async () => {
+    await processResults(bank, NAME, player);
+    const points = await program.account.playerPoints.fetch(
+      pointsPda(program, season, player.publicKey)
+    );
+    assert.equal(points.score.toNumber(), 6);
+  });
+});
@@ -0,0 +1,193 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  CLEANUP_RETENTION,
+  RESOLUTION_TIME,
+  claimPrize,
+  eventPda,
+  expectError,
+  initializeEvent,
+  nextSlot,
+  pay,
+  predict,
+  predictionPda,
+  processResults,
+  resolve,
+  resultsPda,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const OPEN_UNTIL_TIME = 900_000;
+const UNPAUSED = {
+  creation: false,
+  predictions: false,
+  resolution: false,
+  processing: false,
+  payouts: false,
+};
+
+describe("pause", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const player = Keypair.generate();
+
+  const setPause = async (pause: Partial<typeof UNPAUSED>) => {
+    await nextSlot(context);
+    await program.methods
+      .setPause({ ...UNPAUSED, ...pause })
+      .accounts({ authority: maker.publicKey })
+      .rpc();
+  };
+
+  const createEvent = async (name: string) => {
+    await warpTo(context, 0);
+    await initializeEvent(program, maker, season, name, {
+      openUntilTime: OPEN_UNTIL_TIME,
+    });
+  };
+
+  before(async () => {
+    bank = await setupBankrun([player]);
+    ({ context, program, maker, season } = bank);
+  });
+
+  it("rejects new events while creation is paused", async () => {
+    await setPause({ creation: true });
+    await expectError(createEvent("Pause0"), "CreationPaused");
+    await setPause({});
+    await createEvent("Pause0");
+  });
+
+  it("rejects predictions while predictions are paused", async () => {
+    await setPause({ predictions: true });
+    await expectError(predict(bank, "Pause0", player), "PredictionsPaused");
+    await createEvent("Pause00");
+    await setPause({});
+    await predict(bank, "Pause0", player);
+  });
+
+  it("rejects resolution while resolution is paused", async () => {
+    await createEvent("Pause1");
+    await predict(bank, "Pause1", player);
+    await warpTo(context, RESOLUTION_TIME);
+    await setPause({ resolution: true });
+    await expectError(resolve(bank, "Pause1"), "ResolutionPaused");
+    await setPause({});
+    await resolve(bank, "Pause1");
+  });
+
+  it("rejects result processing while processing is paused", async () => {
+    await setPause({ processing: true });
+    await expectError(
+      processResults(bank, "Pause1", player),
+      "ProcessingPaused"
+    );
+    await setPause({});
+    await processResults(bank, "Pause1", player);
+  });
+
+  it("stops payouts while still accepting picks", async () => {
+    await setPause({ payouts: true });
+    await expectError(pay(bank, "Pause1"), "PayoutsPaused");
+
+    await createEvent("Pause2");
+    await predict(bank, "Pause2", player);
+
+    await setPause({});
+    await pay(bank, "Pause1");
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, "Pause1")
+    );
+    assert.isTrue(event.payed);
+  });
+
+  it("blocks cancel and abandon while resolution is paused", async () => {
+    const cancel = () =>
+      program.methods
+        .cancelEvent("Pause3")
+        .accountsPartial({ resolver: maker.publicKey, global: season })
+        .rpc();
+    const abandon = () =>
+      program.methods
+        .abandonEvent("Pause4")
+        .accountsPartial({
+          oracleEvent: eventPda(program, "Pause4"),
+          global: season,
+        })
+        .rpc();
+    await createEvent("Pause3");
+    await createEvent("Pause4");
+    await warpTo(context, RESOLUTION_TIME + 1);
+
+    await setPause({ resolution: true });
+    await expectError(cancel(), "ResolutionPaused");
+    await expectError(abandon(), "ResolutionPaused");
+    await setPause({});
+    await cancel();
+    await abandon();
+  });
+
+  it("stops the closing cranks while payouts are paused", async () => {
+    const oracleEvent = eventPda(program, "Pause1");
+    const closeEvent = () =>
+      program.methods
+        .closeEventPdas()
+        .accountsPartial({
+          cranker: maker.publicKey,
+          oracleEvent,
+          results: resultsPda(program, "Pause1"),
+          maker: maker.publicKey,
+        })
+        .rpc();
+    const closeSeason = () =>
+      program.methods
+        .closeLeaderboardPdas()
+        .accountsPartial({
+          signer: maker.publicKey,
+          global: season,
+          openedBy: maker.publicKey,
+          projectTreasury: maker.publicKey,
+        })
+        .rpc();
+    await claimPrize(bank, "Pause1", player);
+    await program.methods
+      .closePredictionPda()
+      .accountsPartial({
+        owner: player.publicKey,
+        oracleEvent,
+        prediction: predictionPda(program, "Pause1", player.publicKey),
+      })
+      .signers([player])
+      .rpc();
+    const { payedAt } = await program.account.oracleEvent.fetch(oracleEvent);
+    await warpTo(context, payedAt.toNumber() + CLEANUP_RETENTION);
+
+    await setPause({ payouts: true });
+    await expectError(closeEvent(), "PayoutsPaused");
+    // The pause is checked first, so even an open season reports it.
+    await expectError(closeSeason(), "PayoutsPaused");
+    await setPause({});
+    await expectError(closeSeason(), "SeasonOpen");
+    await closeEvent();
+    assert.isNull(await context.banksClient.getAccount(oracleEvent));
+  });
+
+  it("only lets the config authority change the pause flags", async () => {
+    await expectError(
+      program.methods
+        .setPause({ ...UNPAUSED, payouts: true })
+        .accounts({ authority: player.publicKey })
+        .signers([player])
+        .rpc(),
+      "RequireKeysEqViolated"
+    );
+  });
+});
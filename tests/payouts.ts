@@ -0,0 +1,95 @@
+import { BN } from "@coral-xyz/anchor";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  closeSeason,
+  eventPda,
+  expectError,
+  initializeEvent,
+  pay,
+  payGlobal,
+  predict,
+  processResults,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Payout table";
+
+describe("payout tables", () => {
+  let bank: Bankrun;
+  const first = Keypair.generate();
+  const second = Keypair.generate();
+  const third = Keypair.generate();
+
+  // Three entries of 0.1 SOL, less the season's 1% cut.
+  const prizePool = (3 * LAMPORTS_PER_SOL * 99) / 1_000;
+  const seasonPool = (3 * LAMPORTS_PER_SOL) / 1_000;
+
+  const setGlobalPayout = (payout: number[]) =>
+    bank.program.methods
+      .setGlobalPayout(payout)
+      .accountsPartial({
+        authority: bank.maker.publicKey,
+        global: bank.season,
+      })
+      .rpc();
+
+  const amounts = (prizes: { winner: PublicKey; amount: BN }[]) =>
+    prizes.map(({ winner, amount }) => [winner.toBase58(), amount.toNumber()]);
+
+  before(async () => {
+    bank = await setupBankrun([first, second, third]);
+    const { program, maker, season } = bank;
+    await expectError(
+      initializeEvent(program, maker, season, NAME, {
+        payout: [6_000, 3_000],
+      }),
+      "InvalidPayout"
+    );
+    await initializeEvent(program, maker, season, NAME, {
+      payout: [6_000, 3_000, 1_000],
+    });
+    await predict(bank, NAME, first, Buffer.from([1, 2, 3]));
+    await predict(bank, NAME, second, Buffer.from([1, 3, 2]));
+    await predict(bank, NAME, third, Buffer.from([4, 5, 6]));
+  });
+
+  it("pays every place of the event's table", async () => {
+    const { context, program } = bank;
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, NAME);
+    for (const player of [first, second, third]) {
+      await processResults(bank, NAME, player);
+    }
+    await pay(bank, NAME);
+
+    const { prizes } = await program.account.oracleEvent.fetch(
+      eventPda(program, NAME)
+    );
+    assert.deepEqual(amounts(prizes), [
+      [first.publicKey.toBase58(), (prizePool * 6) / 10],
+      [second.publicKey.toBase58(), (prizePool * 3) / 10],
+      [third.publicKey.toBase58(), prizePool / 10],
+    ]);
+  });
+
+  it("pays the season by its own table", async () => {
+    const { program, season } = bank;
+    await expectError(setGlobalPayout([5_000]), "InvalidPayout");
+    await setGlobalPayout([7_000, 3_000]);
+    await closeSeason(bank);
+    await payGlobal(bank);
+
+    // Only two places are paid, so the third ranked player gets nothing.
+    const { prizes } = await program.account.leaderboard.fetch(season);
+    assert.deepEqual(amounts(prizes), [
+      [first.publicKey.toBase58(), (seasonPool * 7) / 10],
+      [second.publicKey.toBase58(), (seasonPool * 3) / 10],
+    ]);
+    await expectError(setGlobalPayout([10_000]), "RequireEqViolated");
+  });
+});
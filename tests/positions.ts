@@ -0,0 +1,74 @@
+import { Keypair } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Bankrun,
+  PICKS,
+  PLACES,
+  RESOLUTION_TIME,
+  eventPda,
+  expectError,
+  initializeEvent,
+  pointsPda,
+  predict,
+  processResults,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Top eight";
+
+describe("top-n events", () => {
+  let bank: Bankrun;
+  const perfect = Keypair.generate();
+  const swapped = Keypair.generate();
+
+  // Athletes 1 to 8 finish in order.
+  const order = [1, 2, 3, 4, 5, 6, 7, 8];
+
+  before(async () => {
+    bank = await setupBankrun([perfect, swapped]);
+    const { program, maker, season } = bank;
+    await initializeEvent(program, maker, season, NAME, {
+      positions: 8,
+      scoring: {
+        exact: [8, 7, 6, 5, 4, 3, 2, 1],
+        onPodium: 0,
+        perfectBonus: 0,
+      },
+    });
+  });
+
+  it("takes one pick per position", async () => {
+    await expectError(predict(bank, NAME, perfect, PICKS), "InvalidPrediction");
+    await predict(bank, NAME, perfect, Buffer.from(order));
+    // The first two swapped and an athlete outside the top eight last.
+    await predict(bank, NAME, swapped, Buffer.from([2, 1, 3, 4, 5, 6, 7, 9]));
+  });
+
+  it("resolves all eight places and scores every slot", async () => {
+    const { context, program, season } = bank;
+    await warpTo(context, RESOLUTION_TIME);
+    await expectError(resolve(bank, NAME, PLACES), "InvalidPodium");
+    await resolve(bank, NAME, order.map((athlete) => Buffer.from([athlete])));
+    await processResults(bank, NAME, perfect);
+    await processResults(bank, NAME, swapped);
+
+    const score = async (player: Keypair) =>
+      (
+        await program.account.playerPoints.fetch(
+          pointsPda(program, season, player.publicKey)
+        )
+      ).score.toNumber();
+    assert.equal(await score(perfect), 36);
+    assert.equal(await score(swapped), 20);
+
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, NAME)
+    );
+    assert.deepEqual(
+      event.leaderboard.map((player) => player.pubkey.toBase58()),
+      [perfect.publicKey.toBase58(), swapped.publicKey.toBase58()]
+    );
+  });
+});
@@ -0,0 +1,83 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  PLACES,
+  RESOLUTION_TIME,
+  eventPda,
+  expectError,
+  initializeEvent,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const OTHER = [Buffer.from([3]), Buffer.from([2]), Buffer.from([1])];
+const THIRD = [Buffer.from([2]), Buffer.from([1]), Buffer.from([3])];
+
+describe("resolver quorum", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const resolvers = [0, 1, 2].map(() => Keypair.generate());
+
+  const vote = (name: string, resolver: Keypair, places: Buffer[]) =>
+    resolve(bank, name, places, resolver);
+
+  const solvedAt = async (name: string) => {
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, name)
+    );
+    return event.solvedAt.toNumber();
+  };
+
+  // Creates an event decided by two of the three resolvers.
+  const createEvent = async (name: string) => {
+    await warpTo(context, 0);
+    await initializeEvent(program, maker, season, name);
+    await program.methods
+      .setResolvers(name, resolvers.map((resolver) => resolver.publicKey), 2)
+      .accounts({ resolver: maker.publicKey })
+      .rpc();
+    await warpTo(context, RESOLUTION_TIME);
+  };
+
+  before(async () => {
+    bank = await setupBankrun(resolvers);
+    ({ context, program, maker, season } = bank);
+  });
+
+  it("resolves once two votes agree, whatever the first vote said", async () => {
+    await createEvent("Quorum0");
+    await vote("Quorum0", resolvers[0], OTHER);
+    await vote("Quorum0", resolvers[1], PLACES);
+    assert.equal(await solvedAt("Quorum0"), 0);
+
+    await vote("Quorum0", resolvers[2], PLACES);
+    assert.equal(await solvedAt("Quorum0"), RESOLUTION_TIME);
+    const { podium } = await program.account.oracleEvent.fetch(
+      eventPda(program, "Quorum0")
+    );
+    assert.deepEqual(podium.places.map((place) => [...place]), [[1], [2], [3]]);
+  });
+
+  it("rejects a vote that leaves no podium a quorum", async () => {
+    await createEvent("Quorum1");
+    await vote("Quorum1", resolvers[0], OTHER);
+    await vote("Quorum1", resolvers[1], PLACES);
+    await expectError(
+      vote("Quorum1", resolvers[2], THIRD),
+      "ConflictingVotes"
+    );
+    assert.equal(await solvedAt("Quorum1"), 0);
+
+    // The earlier votes still stand, so the last resolver can settle it.
+    await vote("Quorum1", resolvers[2], OTHER);
+    assert.equal(await solvedAt("Quorum1"), RESOLUTION_TIME);
+  });
+});
@@ -0,0 +1,96 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  initializeEvent,
+  pda,
+  pointsPda,
+  predict,
+  processResults,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Rankings";
+const RANKINGS_PAGE = 200;
+
+describe("season rankings", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  let rankings: PublicKey;
+  // The first player calls the whole podium, the second only third place.
+  const players = [Keypair.generate(), Keypair.generate()];
+  const picks = [Buffer.from([1, 2, 3]), Buffer.from([2, 1, 3])];
+
+  const rankOf = (player: Keypair) =>
+    program.methods
+      .playerRank()
+      .accountsPartial({
+        global: season,
+        playerPoints: pointsPda(program, season, player.publicKey),
+        rankings,
+      })
+      .view();
+
+  before(async () => {
+    bank = await setupBankrun(players);
+    ({ context, program, maker, season } = bank);
+    rankings = pda(program, Buffer.from("rankings"), season.toBuffer());
+    await program.methods
+      .openRankings()
+      .accountsPartial({ authority: maker.publicKey, global: season })
+      .rpc();
+  });
+
+  it("ranks players by season score", async () => {
+    await initializeEvent(program, maker, season, NAME);
+    for (const [i, player] of players.entries()) {
+      await predict(bank, NAME, player, picks[i]);
+    }
+
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, NAME);
+    for (const player of players) {
+      await processResults(bank, NAME, player);
+    }
+
+    assert.equal(await rankOf(players[0]), 0);
+    // Ranked worst first, so the second insert has to go in front.
+    for (const player of [...players].reverse()) {
+      await program.methods
+        .rankPlayer()
+        .accountsPartial({
+          global: season,
+          playerPoints: pointsPda(program, season, player.publicKey),
+          rankings,
+        })
+        .rpc();
+    }
+
+    assert.equal(await rankOf(players[0]), 1);
+    assert.equal(await rankOf(players[1]), 2);
+    const account = await program.account.rankings.fetch(rankings);
+    assert.equal(account.len, 2);
+    assert.isTrue(account.season.equals(season));
+  });
+
+  it("grows a page at a time", async () => {
+    await program.methods
+      .growRankings()
+      .accountsPartial({ authority: maker.publicKey, global: season })
+      .rpc();
+
+    const account = await program.account.rankings.fetch(rankings);
+    assert.equal(account.capacity, 2 * RANKINGS_PAGE);
+    assert.equal(account.len, 2);
+    assert.equal(await rankOf(players[0]), 1);
+  });
+});
@@ -0,0 +1,186 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  CLEANUP_RETENTION,
+  FEES,
+  RESOLUTION_TIME,
+  claimPrize,
+  cleanupReward,
+  closePlayerPoints,
+  closeSeason,
+  eventPda,
+  expectError,
+  initializeEvent,
+  lamports,
+  nextSlot,
+  pay,
+  payGlobal,
+  pda,
+  predict,
+  predictionPda,
+  processResults,
+  resolve,
+  resultsPda,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Rent";
+
+describe("rent reclamation", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  let oracleEvent: PublicKey;
+  let prediction: PublicKey;
+  const player = Keypair.generate();
+  const stranger = Keypair.generate();
+
+  const closePrediction = (owner: Keypair) =>
+    program.methods
+      .closePredictionPda()
+      .accountsPartial({ owner: owner.publicKey, oracleEvent, prediction })
+      .signers([owner])
+      .rpc();
+
+  before(async () => {
+    bank = await setupBankrun([player, stranger]);
+    ({ context, program, maker, season } = bank);
+    await initializeEvent(program, maker, season, NAME);
+    oracleEvent = eventPda(program, NAME);
+    prediction = predictionPda(program, NAME, player.publicKey);
+    await predict(bank, NAME, player);
+
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, NAME);
+    await processResults(bank, NAME, player);
+  });
+
+  it("returns a settled prediction's rent to its player after payout", async () => {
+    await expectError(closePrediction(player), "EventClose");
+    await pay(bank, NAME);
+    await expectError(closePrediction(stranger), "ConstraintHasOne");
+
+    // The first attempt failed, so the retry needs a fresh blockhash.
+    await nextSlot(context);
+    const rent = await lamports(context, prediction);
+    const before = await context.banksClient.getBalance(player.publicKey);
+    await closePrediction(player);
+    const after = await context.banksClient.getBalance(player.publicKey);
+    assert.equal(after - before, BigInt(rent));
+    assert.isNull(await context.banksClient.getAccount(prediction));
+  });
+
+  it("returns the event's rent to its maker", async () => {
+    await claimPrize(bank, NAME, player);
+    const { payedAt } = await program.account.oracleEvent.fetch(oracleEvent);
+    await warpTo(context, payedAt.toNumber() + CLEANUP_RETENTION);
+
+    const results = resultsPda(program, NAME);
+    const eventRent = await lamports(context, oracleEvent);
+    const resultsRent = await lamports(context, results);
+    // The stranger pays the transaction fee, so the maker's balance only
+    // moves by the rent.
+    const tx = await program.methods
+      .closeEventPdas()
+      .accountsPartial({
+        cranker: stranger.publicKey,
+        oracleEvent,
+        results,
+        maker: maker.publicKey,
+      })
+      .transaction();
+    tx.recentBlockhash = context.lastBlockhash;
+    tx.feePayer = stranger.publicKey;
+    tx.sign(stranger);
+    const before = await context.banksClient.getBalance(maker.publicKey);
+    await context.banksClient.processTransaction(tx);
+    const after = await context.banksClient.getBalance(maker.publicKey);
+
+    assert.equal(
+      after - before,
+      BigInt(
+        eventRent -
+          cleanupReward(eventRent) +
+          resultsRent -
+          cleanupReward(resultsRent)
+      )
+    );
+    assert.isNull(await context.banksClient.getAccount(oracleEvent));
+    assert.isNull(await context.banksClient.getAccount(results));
+  });
+
+  it("returns a season's rent to its opener once every prize is claimed", async () => {
+    const treasury = Keypair.generate();
+    const rankings = pda(program, Buffer.from("rankings"), season.toBuffer());
+    const closeLeaderboard = () =>
+      program.methods
+        .closeLeaderboardPdas()
+        .accountsPartial({
+          signer: stranger.publicKey,
+          global: season,
+          openedBy: maker.publicKey,
+          projectTreasury: treasury.publicKey,
+        })
+        .signers([stranger])
+        .rpc();
+    await program.methods
+      .updateConfig(treasury.publicKey, FEES)
+      .accounts({ authority: maker.publicKey })
+      .rpc();
+    await program.methods
+      .openRankings()
+      .accountsPartial({ authority: maker.publicKey, global: season })
+      .rpc();
+    await expectError(closeLeaderboard(), "SeasonOpen");
+
+    await closeSeason(bank);
+    await payGlobal(bank, { projectTreasury: treasury.publicKey });
+    await nextSlot(context);
+    await expectError(closeLeaderboard(), "UnclaimedPrizes");
+
+    await program.methods
+      .claimGlobalPrize()
+      .accountsPartial({ winner: player.publicKey, global: season })
+      .signers([player])
+      .rpc();
+    await nextSlot(context);
+    await expectError(closeLeaderboard(), "OpenPlayerPoints");
+    await closePlayerPoints(bank, player);
+
+    // Lamports sent to the season after payout aren't anyone's rent.
+    const stray = 1_000_000;
+    await program.provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: maker.publicKey,
+          toPubkey: season,
+          lamports: stray,
+        })
+      )
+    );
+    const seasonRent = (await lamports(context, season)) - stray;
+    const rankingsRent = await lamports(context, rankings);
+    const before = await context.banksClient.getBalance(maker.publicKey);
+    await nextSlot(context);
+    await closeLeaderboard();
+    const after = await context.banksClient.getBalance(maker.publicKey);
+
+    assert.equal(await lamports(context, treasury.publicKey), stray);
+    // The maker also pays the transaction fee for both signatures.
+    assert.equal(after - before, BigInt(seasonRent + rankingsRent - 2 * 5_000));
+    assert.isNull(await context.banksClient.getAccount(season));
+    assert.isNull(await context.banksClient.getAccount(rankings));
+  });
+});
@@ -0,0 +1,145 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  PLACES,
+  RESOLUTION_TIME,
+  claimRefund,
+  eventPda,
+  expectError,
+  initializeEvent,
+  predict,
+  resolve,
+  setupBankrun,
+  warpTo as warpClock,
+} from "./helpers";
+
+const OPEN_UNTIL_TIME = 900_000;
+const GRACE_PERIOD = 3_600;
+const FEE = new BN(LAMPORTS_PER_SOL / 10);
+
+describe("resolver timeout", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const player = Keypair.generate();
+  const backup = Keypair.generate();
+  const resolver = Keypair.generate();
+
+  const warpTo = (unixTimestamp: number) => warpClock(context, unixTimestamp);
+
+  const abandon = (name: string) =>
+    program.methods
+      .abandonEvent(name)
+      .accountsPartial({ oracleEvent: eventPda(program, name), global: season })
+      .rpc();
+
+  const createEvent = async (name: string, backupResolver: PublicKey) => {
+    await warpTo(0);
+    await initializeEvent(program, maker, season, name, {
+      openUntilTime: OPEN_UNTIL_TIME,
+      resolver: resolver.publicKey,
+      fee: FEE,
+      gracePeriod: GRACE_PERIOD,
+      backupResolver,
+    });
+  };
+
+  before(async () => {
+    bank = await setupBankrun([player, backup]);
+    ({ context, program, maker, season } = bank);
+  });
+
+  it("can't be abandoned before the grace period ends", async () => {
+    const name = "Timeout0";
+    await createEvent(name, PublicKey.default);
+    await warpTo(RESOLUTION_TIME + GRACE_PERIOD);
+    await expectError(
+      abandon(name),
+      "ResolverGracePeriod"
+    );
+  });
+
+  it("refunds players once abandoned past the deadline", async () => {
+    const name = "Timeout1";
+    await createEvent(name, PublicKey.default);
+    await predict(bank, name, player);
+
+    await warpTo(RESOLUTION_TIME + GRACE_PERIOD + 1);
+    await abandon(name);
+
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, name)
+    );
+    assert.isTrue(event.abandoned);
+
+    const before = await context.banksClient.getBalance(player.publicKey);
+    await claimRefund(bank, name, player);
+    const after = await context.banksClient.getBalance(player.publicKey);
+    assert.isTrue(after - before >= BigInt(FEE.toString()));
+
+    await expectError(
+      resolve(bank, name, PLACES, resolver),
+      "EventAbandoned"
+    );
+  });
+
+  it("hands resolution to the backup resolver after the deadline", async () => {
+    const name = "Timeout2";
+    await createEvent(name, backup.publicKey);
+
+    await warpTo(RESOLUTION_TIME + GRACE_PERIOD);
+    await expectError(
+      resolve(bank, name, PLACES, backup),
+      "ResolverGracePeriod"
+    );
+
+    await warpTo(RESOLUTION_TIME + GRACE_PERIOD + 1);
+    await expectError(
+      abandon(name),
+      "ResolverGracePeriod"
+    );
+    await resolve(bank, name, PLACES, backup);
+
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, name)
+    );
+    assert.deepEqual([...event.podium.places[0]], [1]);
+    assert.notEqual(event.solvedAt.toNumber(), 0);
+  });
+
+  it("never times out a grace period too long to add up", async () => {
+    const name = "Timeout4";
+    await warpTo(0);
+    await initializeEvent(program, maker, season, name, {
+      openUntilTime: OPEN_UNTIL_TIME,
+      resolver: resolver.publicKey,
+      gracePeriod: new BN("9223372036854775807"),
+      backupResolver: backup.publicKey,
+    });
+
+    await warpTo(RESOLUTION_TIME + 2 * GRACE_PERIOD + 1);
+    await expectError(abandon(name), "ResolverGracePeriod");
+    await expectError(
+      resolve(bank, name, PLACES, backup),
+      "ResolverGracePeriod"
+    );
+  });
+
+  it("lets anyone abandon once the backup resolver also times out", async () => {
+    const name = "Timeout3";
+    await createEvent(name, backup.publicKey);
+    await warpTo(RESOLUTION_TIME + 2 * GRACE_PERIOD + 1);
+    await abandon(name);
+
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, name)
+    );
+    assert.isTrue(event.abandoned);
+  });
+});
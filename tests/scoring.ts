@@ -0,0 +1,50 @@
+import { Keypair } from "@solana/web3.js";
+import { assert } from "chai";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  initializeEvent,
+  predict,
+  predictionPda,
+  processResults,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Custom scoring";
+
+describe("scoring schedules", () => {
+  let bank: Bankrun;
+  const perfect = Keypair.generate();
+  const reordered = Keypair.generate();
+  const scattered = Keypair.generate();
+
+  before(async () => {
+    bank = await setupBankrun([perfect, reordered, scattered]);
+    const { program, maker, season } = bank;
+    await initializeEvent(program, maker, season, NAME, {
+      scoring: { exact: [10, 6, 3], onPodium: 1, perfectBonus: 5 },
+    });
+    await predict(bank, NAME, perfect, Buffer.from([1, 2, 3]));
+    await predict(bank, NAME, reordered, Buffer.from([1, 3, 2]));
+    await predict(bank, NAME, scattered, Buffer.from([2, 1, 4]));
+  });
+
+  it("scores each prediction by the event's own schedule", async () => {
+    const { context, program } = bank;
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, NAME);
+    const points = async (player: Keypair) => {
+      await processResults(bank, NAME, player);
+      const prediction = await program.account.podiumPrediction.fetch(
+        predictionPda(program, NAME, player.publicKey)
+      );
+      return prediction.points.toNumber();
+    };
+    assert.equal(await points(perfect), 10 + 6 + 3 + 5);
+    // Podium athletes in the wrong slot earn onPodium, others nothing.
+    assert.equal(await points(reordered), 10 + 1 + 1);
+    assert.equal(await points(scattered), 1 + 1);
+  });
+});
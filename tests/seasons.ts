@@ -0,0 +1,84 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  closeSeason,
+  eventPda,
+  expectError,
+  initializeEvent,
+  pay,
+  payGlobal,
+  pda,
+  resolve,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+
+describe("seasons", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let paris: PublicKey;
+  let milano: PublicKey;
+
+  const createEvent = async (name: string, season: PublicKey) => {
+    await warpTo(context, 0);
+    await initializeEvent(program, maker, season, name);
+  };
+
+  before(async () => {
+    bank = await setupBankrun();
+    ({ context, program, maker, season: paris } = bank);
+    await program.methods
+      .openSeason("Milano-Cortina 2026")
+      .accounts({ authority: maker.publicKey })
+      .rpc();
+    milano = pda(
+      program,
+      Buffer.from("season"),
+      Buffer.from("Milano-Cortina 2026")
+    );
+  });
+
+  it("counts events in the season they were created in", async () => {
+    await createEvent("Season0", paris);
+    await createEvent("Season1", milano);
+
+    const event = await program.account.oracleEvent.fetch(
+      eventPda(program, "Season0")
+    );
+    assert.isTrue(event.season.equals(paris));
+    assert.equal(
+      (await program.account.leaderboard.fetch(paris)).events.toNumber(),
+      1
+    );
+    assert.equal(
+      (await program.account.leaderboard.fetch(milano)).events.toNumber(),
+      1
+    );
+  });
+
+  it("pays a closed season without touching the others", async () => {
+    await expectError(payGlobal(bank), "SeasonOpen");
+
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, "Season0");
+    await pay(bank, "Season0");
+    await closeSeason(bank);
+    await expectError(createEvent("Season2", paris), "SeasonClosed");
+
+    await payGlobal(bank);
+
+    assert.isTrue((await program.account.leaderboard.fetch(paris)).payed);
+    const other = await program.account.leaderboard.fetch(milano);
+    assert.isTrue(other.open);
+    assert.isFalse(other.payed);
+    assert.equal(other.settled.toNumber(), 0);
+  });
+});
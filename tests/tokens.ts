@@ -0,0 +1,538 @@
+import { BN, Program } from "@coral-xyz/anchor";
+import { BankrunProvider } from "anchor-bankrun";
+import { ProgramTestContext } from "solana-bankrun";
+import {
+  AccountLayout,
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMetadataPointerInstruction,
+  createInitializeMint2Instruction,
+  createInitializePermanentDelegateInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+} from "@solana/spl-token";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  CLEANUP_RETENTION,
+  RESOLUTION_TIME,
+  claimPrize,
+  closeSeason,
+  eventPda,
+  expectError,
+  initializeEvent,
+  merkleLeaf,
+  nextSlot,
+  pay,
+  payGlobal,
+  pda,
+  pointsPda,
+  predict,
+  predictionPda,
+  processResults,
+  resolve,
+  resultsPda,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Tokens";
+const DECIMALS = 6;
+const FEE = 1_000_000;
+const PENALTY_BASIS_POINTS = 1_000;
+const PODIUM = Buffer.from([1, 2, 3]);
+const WRONG = Buffer.from([3, 2, 1]);
+
+const createMint = async (
+  provider: BankrunProvider,
+  tokenProgram: PublicKey,
+  extensions: ExtensionType[] = []
+) => {
+  const mint = Keypair.generate();
+  const payer = provider.wallet.publicKey;
+  const space = getMintLen(extensions);
+  const rent = await provider.context.banksClient.getRent();
+  const tx = new Transaction().add(
+    SystemProgram.createAccount({
+      fromPubkey: payer,
+      newAccountPubkey: mint.publicKey,
+      space,
+      lamports: Number(rent.minimumBalance(BigInt(space))),
+      programId: tokenProgram,
+    })
+  );
+  if (extensions.includes(ExtensionType.TransferFeeConfig)) {
+    tx.add(
+      createInitializeTransferFeeConfigInstruction(
+        mint.publicKey,
+        payer,
+        payer,
+        100,
+        BigInt(FEE),
+        tokenProgram
+      )
+    );
+  }
+  if (extensions.includes(ExtensionType.PermanentDelegate)) {
+    tx.add(
+      createInitializePermanentDelegateInstruction(
+        mint.publicKey,
+        payer,
+        tokenProgram
+      )
+    );
+  }
+  if (extensions.includes(ExtensionType.MetadataPointer)) {
+    tx.add(
+      createInitializeMetadataPointerInstruction(
+        mint.publicKey,
+        payer,
+        mint.publicKey,
+        tokenProgram
+      )
+    );
+  }
+  tx.add(
+    createInitializeMint2Instruction(
+      mint.publicKey,
+      DECIMALS,
+      payer,
+      null,
+      tokenProgram
+    )
+  );
+  await provider.sendAndConfirm(tx, [mint]);
+  return mint.publicKey;
+};
+
+// Creates the owner's associated token account and mints `amount` into it.
+const tokenAccount = async (
+  provider: BankrunProvider,
+  mint: PublicKey,
+  tokenProgram: PublicKey,
+  owner: PublicKey,
+  amount = 0
+) => {
+  const payer = provider.wallet.publicKey;
+  const address = getAssociatedTokenAddressSync(
+    mint,
+    owner,
+    true,
+    tokenProgram
+  );
+  const tx = new Transaction().add(
+    createAssociatedTokenAccountIdempotentInstruction(
+      payer,
+      address,
+      owner,
+      mint,
+      tokenProgram
+    )
+  );
+  if (amount > 0) {
+    tx.add(
+      createMintToInstruction(mint, address, payer, amount, [], tokenProgram)
+    );
+  }
+  await provider.sendAndConfirm(tx);
+  return address;
+};
+
+const tokenBalance = async (context: ProgramTestContext, address: PublicKey) =>
+  Number(
+    AccountLayout.decode(
+      Buffer.from((await context.banksClient.getAccount(address)).data)
+    ).amount
+  );
+
+const tokenEvents = (label: string, tokenProgram: PublicKey) =>
+  describe(`${label} events`, () => {
+    let bank: Bankrun;
+    let context: ProgramTestContext;
+    let provider: BankrunProvider;
+    let program: Program<PotreroOlimpcs>;
+    let maker: Keypair;
+    let season: PublicKey;
+    let mint: PublicKey;
+    let oracleEvent: PublicKey;
+    let vault: PublicKey;
+    let globalVault: PublicKey;
+    let makerToken: PublicKey;
+    const winner = Keypair.generate();
+    const loser = Keypair.generate();
+    const leaver = Keypair.generate();
+    const wallets = new Map<Keypair, PublicKey>();
+
+    const tokenAccounts = (player: Keypair) => ({
+      mint,
+      playerToken: wallets.get(player),
+      vault,
+      tokenProgram,
+    });
+
+    const enter = (player: Keypair, picks: Buffer) =>
+      predict(bank, NAME, player, picks, tokenAccounts(player));
+
+    const claimGlobalPrize = (player: Keypair) =>
+      program.methods
+        .claimGlobalTokenPrize()
+        .accountsPartial({
+          winner: player.publicKey,
+          global: season,
+          mint,
+          globalVault,
+          winnerToken: wallets.get(player),
+          tokenProgram,
+        })
+        .signers([player])
+        .rpc();
+
+    before(async () => {
+      bank = await setupBankrun([winner, loser, leaver]);
+      ({ context, provider, program, maker, season } = bank);
+      mint = await createMint(provider, tokenProgram);
+      for (const player of [winner, loser, leaver]) {
+        wallets.set(
+          player,
+          await tokenAccount(
+            provider,
+            mint,
+            tokenProgram,
+            player.publicKey,
+            FEE
+          )
+        );
+      }
+      // The maker is the fee receiver and, through the Config, the treasury.
+      makerToken = await tokenAccount(
+        provider,
+        mint,
+        tokenProgram,
+        maker.publicKey
+      );
+
+      oracleEvent = eventPda(program, NAME);
+      vault = pda(program, Buffer.from("vault"), oracleEvent.toBuffer());
+      globalVault = pda(
+        program,
+        Buffer.from("vault"),
+        season.toBuffer(),
+        mint.toBuffer()
+      );
+      await initializeEvent(program, maker, season, NAME, {
+        fee: new BN(FEE),
+        withdrawPenalty: PENALTY_BASIS_POINTS,
+        accounts: { mint, vault, tokenProgram },
+      });
+    });
+
+    it("takes entry fees into the event vault", async () => {
+      await enter(winner, PODIUM);
+      await enter(loser, WRONG);
+      await enter(leaver, PODIUM);
+
+      assert.equal(await tokenBalance(context, vault), 3 * FEE);
+      assert.equal(await tokenBalance(context, wallets.get(winner)), 0);
+      const event = await program.account.oracleEvent.fetch(oracleEvent);
+      assert.equal(event.pool.toNumber(), 3 * FEE);
+      assert.isTrue(event.mint.equals(mint));
+    });
+
+    it("refunds a withdrawn entry minus the penalty", async () => {
+      await program.methods
+        .withdrawPrediction(NAME)
+        .accountsPartial({
+          player: leaver.publicKey,
+          oracleEvent,
+          ...tokenAccounts(leaver),
+        })
+        .signers([leaver])
+        .rpc();
+
+      const penalty = (FEE * PENALTY_BASIS_POINTS) / 10_000;
+      assert.equal(
+        await tokenBalance(context, wallets.get(leaver)),
+        FEE - penalty
+      );
+      assert.equal(await tokenBalance(context, vault), 2 * FEE + penalty);
+      const event = await program.account.oracleEvent.fetch(oracleEvent);
+      assert.equal(event.pool.toNumber(), 2 * FEE + penalty);
+      assert.equal(event.plays.toNumber(), 2);
+      const points = await program.account.playerPoints.fetch(
+        pointsPda(program, season, leaver.publicKey)
+      );
+      assert.equal(points.events, 0);
+    });
+
+    it("pays the global cut and the winner in tokens", async () => {
+      await warpTo(context, RESOLUTION_TIME);
+      await resolve(bank, NAME);
+      for (const player of [winner, loser]) {
+        await processResults(bank, NAME, player);
+      }
+      const { pool } = await program.account.oracleEvent.fetch(oracleEvent);
+      await pay(bank, NAME, {
+        mint,
+        vault,
+        feeReceiverToken: makerToken,
+        treasuryToken: makerToken,
+        globalVault,
+        tokenProgram,
+      });
+
+      const cut = Math.floor(pool.toNumber() / 100);
+      assert.equal(await tokenBalance(context, globalVault), cut);
+      const [tokenPool] = (await program.account.leaderboard.fetch(season))
+        .tokenPools;
+      assert.isTrue(tokenPool.mint.equals(mint));
+      assert.equal(tokenPool.amount.toNumber(), cut);
+
+      await claimPrize(bank, NAME, winner, {
+        mint,
+        vault,
+        winnerToken: wallets.get(winner),
+        tokenProgram,
+      });
+      assert.equal(
+        await tokenBalance(context, wallets.get(winner)),
+        pool.toNumber() - cut
+      );
+      assert.equal(await tokenBalance(context, vault), 0);
+    });
+
+    it("pays the season's token pool to its leaderboard", async () => {
+      await closeSeason(bank);
+      await payGlobal(bank);
+
+      const before = await tokenBalance(context, wallets.get(winner));
+      await claimGlobalPrize(winner);
+      await claimGlobalPrize(loser);
+      assert.isAbove(await tokenBalance(context, wallets.get(winner)), before);
+      assert.isAbove(await tokenBalance(context, wallets.get(loser)), 0);
+      assert.equal(await tokenBalance(context, globalVault), 0);
+
+      await nextSlot(context);
+      await expectError(claimGlobalPrize(winner), "NoPrize");
+    });
+
+    it("closes the event's vault with the event", async () => {
+      for (const player of [winner, loser]) {
+        await program.methods
+          .closePredictionPda()
+          .accountsPartial({
+            owner: player.publicKey,
+            oracleEvent,
+            prediction: predictionPda(program, NAME, player.publicKey),
+          })
+          .signers([player])
+          .rpc();
+      }
+      const { payedAt } = await program.account.oracleEvent.fetch(oracleEvent);
+      await warpTo(context, payedAt.toNumber() + CLEANUP_RETENTION);
+      await program.methods
+        .closeEventPdas()
+        .accountsPartial({
+          cranker: maker.publicKey,
+          oracleEvent,
+          results: resultsPda(program, NAME),
+          maker: maker.publicKey,
+          mint,
+          vault,
+          tokenProgram,
+        })
+        .rpc();
+      assert.isNull(await context.banksClient.getAccount(vault));
+      assert.isNull(await context.banksClient.getAccount(oracleEvent));
+    });
+  });
+
+tokenEvents("SPL Token", TOKEN_PROGRAM_ID);
+tokenEvents("Token-2022", TOKEN_2022_PROGRAM_ID);
+
+describe("token pools", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const player = Keypair.generate();
+
+  const tokenEvent = (
+    name: string,
+    mint: PublicKey,
+    tokenProgram: PublicKey,
+    global = season
+  ) =>
+    initializeEvent(program, maker, global, name, {
+      fee: new BN(FEE),
+      accounts: {
+        mint,
+        vault: pda(
+          program,
+          Buffer.from("vault"),
+          eventPda(program, name).toBuffer()
+        ),
+        tokenProgram,
+      },
+    });
+
+  before(async () => {
+    bank = await setupBankrun([player]);
+    ({ context, provider, program, maker, season } = bank);
+  });
+
+  it("rejects mints that charge transfer fees", async () => {
+    const mint = await createMint(provider, TOKEN_2022_PROGRAM_ID, [
+      ExtensionType.TransferFeeConfig,
+    ]);
+    await expectError(
+      tokenEvent("Transfer fee", mint, TOKEN_2022_PROGRAM_ID),
+      "UnsupportedMint"
+    );
+  });
+
+  it("rejects mints whose delegate can take the vault's tokens", async () => {
+    const mint = await createMint(provider, TOKEN_2022_PROGRAM_ID, [
+      ExtensionType.MetadataPointer,
+      ExtensionType.PermanentDelegate,
+    ]);
+    await expectError(
+      tokenEvent("Permanent delegate", mint, TOKEN_2022_PROGRAM_ID),
+      "UnsupportedMint"
+    );
+
+    const harmless = await createMint(provider, TOKEN_2022_PROGRAM_ID, [
+      ExtensionType.MetadataPointer,
+    ]);
+    await tokenEvent("Metadata pointer", harmless, TOKEN_2022_PROGRAM_ID);
+    // Cancelled so the season's payout below doesn't wait on it.
+    await program.methods
+      .cancelEvent("Metadata pointer")
+      .accountsPartial({ resolver: maker.publicKey, global: season })
+      .rpc();
+  });
+
+  it("reserves a season's token pool when the event is created", async () => {
+    await program.methods
+      .openSeason("Token limits")
+      .accounts({ authority: maker.publicKey })
+      .rpc();
+    const limits = pda(
+      program,
+      Buffer.from("season"),
+      Buffer.from("Token limits")
+    );
+    for (let i = 0; i < 4; i++) {
+      const mint = await createMint(provider, TOKEN_PROGRAM_ID);
+      await tokenEvent(`Limit ${i}`, mint, TOKEN_PROGRAM_ID, limits);
+    }
+    const { tokenPools } = await program.account.leaderboard.fetch(limits);
+    assert.equal(tokenPools.length, 4);
+    assert.isTrue(tokenPools.every((pool) => pool.amount.isZero()));
+
+    const fifth = await createMint(provider, TOKEN_PROGRAM_ID);
+    await expectError(
+      tokenEvent("Limit 4", fifth, TOKEN_PROGRAM_ID, limits),
+      "TooManyTokenPools"
+    );
+  });
+
+  it("pays a Merkle-paid season's token pool by its own root", async () => {
+    const mint = await createMint(provider, TOKEN_PROGRAM_ID);
+    const playerToken = await tokenAccount(
+      provider,
+      mint,
+      TOKEN_PROGRAM_ID,
+      player.publicKey,
+      FEE
+    );
+    const makerToken = await tokenAccount(
+      provider,
+      mint,
+      TOKEN_PROGRAM_ID,
+      maker.publicKey
+    );
+    const oracleEvent = eventPda(program, NAME);
+    const vault = pda(program, Buffer.from("vault"), oracleEvent.toBuffer());
+    const globalVault = pda(
+      program,
+      Buffer.from("vault"),
+      season.toBuffer(),
+      mint.toBuffer()
+    );
+    await tokenEvent(NAME, mint, TOKEN_PROGRAM_ID);
+    await predict(bank, NAME, player, PODIUM, {
+      mint,
+      playerToken,
+      vault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    await warpTo(context, RESOLUTION_TIME);
+    await resolve(bank, NAME);
+    await processResults(bank, NAME, player);
+    await pay(bank, NAME, {
+      mint,
+      vault,
+      feeReceiverToken: makerToken,
+      treasuryToken: makerToken,
+      globalVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+    await closeSeason(bank);
+
+    const { amount } = (
+      await program.account.leaderboard.fetch(season)
+    ).tokenPools.find((pool) => pool.mint.equals(mint));
+    const root = merkleLeaf(0, player.publicKey, amount);
+    const postTokenDistribution = () =>
+      program.methods
+        .postTokenDistribution(root, amount, 1)
+        .accountsPartial({ authority: maker.publicKey, global: season, mint })
+        .rpc();
+    // Seasons paid by leaderboard pay their token pools by place instead.
+    await expectError(postTokenDistribution(), "AccountNotInitialized");
+    await program.methods
+      .postDistribution(
+        merkleLeaf(0, player.publicKey, new BN(0)),
+        new BN(0),
+        1
+      )
+      .accountsPartial({ authority: maker.publicKey, global: season })
+      .rpc();
+    await nextSlot(context);
+    await postTokenDistribution();
+
+    const claim = () =>
+      program.methods
+        .claimTokenDistribution(0, amount, [])
+        .accountsPartial({
+          winner: player.publicKey,
+          global: season,
+          mint,
+          globalVault,
+          winnerToken: playerToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([player])
+        .rpc();
+    await claim();
+    assert.equal(await tokenBalance(context, playerToken), amount.toNumber());
+    assert.equal(await tokenBalance(context, globalVault), 0);
+
+    await nextSlot(context);
+    await expectError(claim(), "NoPrize");
+  });
+});
@@ -0,0 +1,112 @@
+import { Program } from "@coral-xyz/anchor";
+import { ProgramTestContext } from "solana-bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { assert } from "chai";
+import { PotreroOlimpcs } from "../target/types/potrero_olimpcs";
+import {
+  Bankrun,
+  RESOLUTION_TIME,
+  eventPda,
+  expectError,
+  initializeEvent,
+  lamports,
+  pointsPda,
+  predict,
+  predictionPda,
+  setupBankrun,
+  warpTo,
+} from "./helpers";
+
+const NAME = "Withdrawals";
+const PENALTY_BASIS_POINTS = 1_000;
+
+describe("updating and withdrawing predictions", () => {
+  let bank: Bankrun;
+  let context: ProgramTestContext;
+  let program: Program<PotreroOlimpcs>;
+  let maker: Keypair;
+  let season: PublicKey;
+  const [stayer, leaver, late] = [0, 1, 2].map(() => Keypair.generate());
+  const oracleEvent = () => eventPda(program, NAME);
+
+  const accounts = (player: Keypair) => ({
+    player: player.publicKey,
+    oracleEvent: oracleEvent(),
+    prediction: predictionPda(program, NAME, player.publicKey),
+  });
+
+  const update = (player: Keypair, picks: number[]) =>
+    program.methods
+      .updatePrediction(NAME, Buffer.from(picks))
+      .accountsPartial(accounts(player))
+      .signers([player])
+      .rpc();
+
+  const withdraw = (player: Keypair) =>
+    program.methods
+      .withdrawPrediction(NAME)
+      .accountsPartial({
+        ...accounts(player),
+        playerPoints: pointsPda(program, season, player.publicKey),
+      })
+      .signers([player])
+      .rpc();
+
+  before(async () => {
+    bank = await setupBankrun([stayer, leaver, late]);
+    ({ context, program, maker, season } = bank);
+    await initializeEvent(program, maker, season, NAME, {
+      withdrawPenalty: PENALTY_BASIS_POINTS,
+    });
+    for (const player of [stayer, leaver, late]) {
+      await predict(bank, NAME, player);
+    }
+  });
+
+  it("replaces a player's picks and entry time", async () => {
+    await warpTo(context, 10);
+    await update(stayer, [3, 2, 1]);
+
+    const prediction = await program.account.podiumPrediction.fetch(
+      predictionPda(program, NAME, stayer.publicKey)
+    );
+    assert.deepEqual([...prediction.picks], [3, 2, 1]);
+    assert.equal(prediction.timestamp.toNumber(), 10);
+    await expectError(update(stayer, [1, 1, 2]), "InvalidPrediction");
+  });
+
+  it("refunds a withdrawn entry minus the penalty", async () => {
+    const { fee } = await program.account.oracleEvent.fetch(oracleEvent());
+    const prediction = predictionPda(program, NAME, leaver.publicKey);
+    const rent = await lamports(context, prediction);
+    const before = await lamports(context, leaver.publicKey);
+    await withdraw(leaver);
+
+    const penalty = (fee.toNumber() * PENALTY_BASIS_POINTS) / 10_000;
+    assert.equal(
+      await lamports(context, leaver.publicKey),
+      before + fee.toNumber() - penalty + rent
+    );
+    assert.isNull(await context.banksClient.getAccount(prediction));
+    const event = await program.account.oracleEvent.fetch(oracleEvent());
+    assert.equal(event.plays.toNumber(), 2);
+    assert.equal(event.pool.toNumber(), 2 * fee.toNumber() + penalty);
+    const points = await program.account.playerPoints.fetch(
+      pointsPda(program, season, leaver.publicKey)
+    );
+    assert.equal(points.events, 0);
+  });
+
+  it("rejects changes once predictions close", async () => {
+    await warpTo(context, RESOLUTION_TIME);
+    await expectError(update(stayer, [1, 2, 3]), "EventClose");
+    await expectError(withdraw(late), "EventClose");
+
+    const event = await program.account.oracleEvent.fetch(oracleEvent());
+    assert.equal(event.plays.toNumber(), 2);
+    const points = await program.account.playerPoints.fetch(
+      pointsPda(program, season, late.publicKey)
+    );
+    assert.equal(points.events, 1);
+  });
+});